@@ -1,3 +1,6 @@
+use aoc_common::parallel;
+use clap::Parser;
+
 fn is_invalid_id_part1(id: &str) -> bool {
     let len = id.len();
     if !len.is_multiple_of(2) {
@@ -14,29 +17,42 @@ fn is_invalid_id_part2(id: &str) -> bool {
         .any(|sub_size| id == id[0..sub_size].repeat(len / sub_size))
 }
 
+/// Invalid IDs of every `start-end` range in `line`, scanning the ranges independently.
 fn find_invalid_ids(
     line: &str,
     validator: fn(&str) -> bool,
 ) -> anyhow::Result<Vec<usize>> {
-    let mut invalid_ids = vec![];
-    for mut id_range in line.trim().split(',') {
-        id_range = id_range.trim();
-        if let Some((start, end)) = id_range.split_once('-') {
-            let start: usize = start.parse()?;
-            let end: usize = end.parse()?;
-            for id in start..=end {
-                if validator(&id.to_string()) {
-                    invalid_ids.push(id);
-                }
-            }
-        } else {
-            return Err(anyhow::anyhow!("invalid id {}", id_range));
-        }
-    }
-    Ok(invalid_ids)
+    let ranges = line
+        .trim()
+        .split(',')
+        .map(|id_range| {
+            let id_range = id_range.trim();
+            let Some((start, end)) = id_range.split_once('-') else {
+                anyhow::bail!("invalid id {}", id_range);
+            };
+            Ok(start.parse::<usize>()?..=end.parse::<usize>()?)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let invalid_ids = parallel::map(&ranges, |range| {
+        range
+            .clone()
+            .filter(|id| validator(&id.to_string()))
+            .collect::<Vec<_>>()
+    });
+    Ok(invalid_ids.concat())
+}
+
+#[derive(Parser)]
+struct Args {
+    /// Worker threads for scanning ranges, 0 for one per CPU. More than one needs the
+    /// `parallel` feature.
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
 }
 
 fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    parallel::set_threads(args.threads).map_err(anyhow::Error::msg)?;
     let contents = std::fs::read_to_string("./data/day-02-input.txt")?;
 
     // Part 1
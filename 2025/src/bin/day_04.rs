@@ -1,3 +1,29 @@
+use clap::Parser;
+use std::{
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// Solve day 4, optionally visualising how the grid gets peeled.
+#[derive(Parser)]
+struct Args {
+    /// Print every removal round as a terminal frame.
+    #[arg(long)]
+    animate: bool,
+
+    /// Delay between animation frames in milliseconds.
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    delay: u64,
+
+    /// Plain-text frames without ANSI escapes, e.g. for logs.
+    #[arg(long)]
+    no_color: bool,
+
+    /// Write a heatmap of removal rounds to a `.ppm` or `.png` file.
+    #[arg(long, value_name = "PATH")]
+    heatmap: Option<PathBuf>,
+}
+
 const DIRS: [(i32, i32); 8] =
     [(-1, 1), (0, 1), (1, 1), (-1, 0), (1, 0), (-1, -1), (0, -1), (1, -1)];
 
@@ -25,6 +51,9 @@ fn construct_occupied(contents: &str) -> anyhow::Result<Vec<Vec<bool>>> {
     Ok(occupied)
 }
 
+/// A roll can be reached by a forklift if fewer than this many rolls surround it.
+const ACCESS_THRESHOLD: usize = 4;
+
 fn find_forklift_accessible(
     occupied: &[Vec<bool>],
 ) -> anyhow::Result<Vec<(usize, usize)>> {
@@ -45,7 +74,7 @@ fn find_forklift_accessible(
                     surrounding_count += 1;
                 }
             }
-            if surrounding_count < 4 {
+            if surrounding_count < ACCESS_THRESHOLD {
                 indices.push((r as usize, c as usize))
             }
         }
@@ -54,30 +83,235 @@ fn find_forklift_accessible(
     Ok(indices)
 }
 
-fn find_accessible_iterative(
-    mut occupied: Vec<Vec<bool>>,
-) -> anyhow::Result<usize> {
-    let indices = find_forklift_accessible(&occupied)?;
-    let mut last_found = indices.len();
-    for (r, c) in indices.iter() {
-        occupied[*r][*c] = false
+/// Outcome of peeling a grid down to its stable core.
+#[derive(Debug)]
+struct Peeling {
+    /// removal_round[r][c] -> 1-based round in which the roll at (r, c) was removed, `None` if
+    /// the cell was empty or the roll survived
+    removal_round: Vec<Vec<Option<usize>>>,
+    /// Total number of rolls removed
+    removed: usize,
+    /// Number of rounds in which at least one roll was removed
+    rounds: usize,
+}
+
+/// k-core style peeling: repeatedly remove every occupied cell with fewer than `threshold`
+/// occupied cells in its `neighbourhood`, where all removals within a round happen at once.
+///
+/// Each cell keeps a count of its occupied neighbours and cells are queued as soon as their count
+/// drops below the threshold, so a removal only touches its own neighbourhood and the whole
+/// peeling is O(cells * neighbourhood) rather than a full rescan per round.
+///
+/// See: <https://en.wikipedia.org/wiki/Degeneracy_(graph_theory)#k-Cores>
+fn peel(
+    occupied: &[Vec<bool>],
+    threshold: usize,
+    neighbourhood: &[(i32, i32)],
+) -> Peeling {
+    let rows = occupied.len() as i32;
+    let cols = occupied.first().map_or(0, |row| row.len()) as i32;
+    let in_bounds = |r: i32, c: i32| r >= 0 && r < rows && c >= 0 && c < cols;
+
+    // counts[r][c] -> occupied cells in the neighbourhood of (r, c)
+    let mut counts = vec![vec![0usize; cols as usize]; rows as usize];
+    for r in 0..rows {
+        for c in 0..cols {
+            if !occupied[r as usize][c as usize] {
+                continue;
+            }
+            counts[r as usize][c as usize] = neighbourhood
+                .iter()
+                .map(|(dr, dc)| (r + dr, c + dc))
+                .filter(|&(nr, nc)| {
+                    in_bounds(nr, nc) && occupied[nr as usize][nc as usize]
+                })
+                .count();
+        }
+    }
+
+    let mut removal_round = vec![vec![None; cols as usize]; rows as usize];
+    let mut queue: Vec<(i32, i32)> = Vec::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            if occupied[r as usize][c as usize]
+                && counts[r as usize][c as usize] < threshold
+            {
+                removal_round[r as usize][c as usize] = Some(1);
+                queue.push((r, c));
+            }
+        }
+    }
+
+    let (mut removed, mut rounds) = (0, 0);
+    while !queue.is_empty() {
+        rounds += 1;
+        removed += queue.len();
+        let mut next_queue = Vec::new();
+        for (r, c) in queue {
+            // (r, c) is a neighbour of every cell that reaches it through some offset, which is
+            // the reverse offset when the neighbourhood isn't symmetric
+            for (dr, dc) in neighbourhood {
+                let (nr, nc) = (r - dr, c - dc);
+                if !in_bounds(nr, nc) || !occupied[nr as usize][nc as usize] {
+                    continue;
+                }
+                let (nr, nc) = (nr as usize, nc as usize);
+                counts[nr][nc] -= 1;
+                if removal_round[nr][nc].is_none()
+                    && counts[nr][nc] < threshold
+                {
+                    removal_round[nr][nc] = Some(rounds + 1);
+                    next_queue.push((nr as i32, nc as i32));
+                }
+            }
+        }
+        queue = next_queue;
+    }
+
+    Peeling { removal_round, removed, rounds }
+}
+
+/// Side length in pixels of a single grid cell in the heatmap.
+const HEATMAP_CELL_PIXELS: usize = 4;
+
+/// Renders the grid as it stands at the start of `round`, highlighting the rolls removed during
+/// that round. Without color those rolls are drawn as `x` instead.
+fn write_frame(
+    out: &mut impl Write,
+    peeling: &Peeling,
+    occupied: &[Vec<bool>],
+    round: usize,
+    color: bool,
+) -> anyhow::Result<()> {
+    let removed_now = peeling
+        .removal_round
+        .iter()
+        .flatten()
+        .filter(|&&removed| removed == Some(round))
+        .count();
+    if color {
+        // clear screen and move cursor home so frames replace each other
+        write!(out, "\x1b[2J\x1b[H")?;
+    }
+    writeln!(
+        out,
+        "Round {}/{}: {} removed",
+        round, peeling.rounds, removed_now
+    )?;
+    for (r, row) in occupied.iter().enumerate() {
+        for (c, &cell) in row.iter().enumerate() {
+            let glyph = match (cell, peeling.removal_round[r][c]) {
+                (false, _) => ".",
+                (true, Some(removed)) if removed < round => ".",
+                (true, Some(removed)) if removed == round => {
+                    if color {
+                        "\x1b[1;31m@\x1b[0m"
+                    } else {
+                        "x"
+                    }
+                }
+                (true, _) => "@",
+            };
+            write!(out, "{}", glyph)?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Print every round of the peeling, pausing `delay` between frames.
+fn animate(
+    peeling: &Peeling,
+    occupied: &[Vec<bool>],
+    delay: std::time::Duration,
+    color: bool,
+) -> anyhow::Result<()> {
+    let mut out = std::io::stdout().lock();
+    for round in 1..=peeling.rounds {
+        write_frame(&mut out, peeling, occupied, round, color)?;
+        out.flush()?;
+        std::thread::sleep(delay);
     }
-    let mut total_count = last_found;
+    Ok(())
+}
 
-    // iterative fixpoint
-    while last_found != 0 {
-        let indices = find_forklift_accessible(&occupied)?;
-        for (r, c) in indices.iter() {
-            occupied[*r][*c] = false
+/// Heatmap color for a cell: black for empty cells, white for rolls that are never removed, and a
+/// blue (early) to red (late) gradient over the removal rounds.
+fn heat_color(
+    removal_round: Option<usize>,
+    occupied: bool,
+    rounds: usize,
+) -> [u8; 3] {
+    match (occupied, removal_round) {
+        (false, _) => [0, 0, 0],
+        (true, None) => [255, 255, 255],
+        (true, Some(round)) => {
+            let t = if rounds > 1 {
+                (round - 1) as f64 / (rounds - 1) as f64
+            } else {
+                0.0
+            };
+            [(255.0 * t) as u8, 0, (255.0 * (1.0 - t)) as u8]
         }
-        last_found = indices.len();
-        total_count += last_found
     }
+}
 
-    Ok(total_count)
+/// Row-major RGB pixels of the removal heatmap, scaled up by [`HEATMAP_CELL_PIXELS`].
+fn heatmap_pixels(
+    peeling: &Peeling,
+    occupied: &[Vec<bool>],
+) -> (usize, usize, Vec<u8>) {
+    let rows = occupied.len();
+    let cols = occupied.first().map_or(0, |row| row.len());
+    let (width, height) =
+        (cols * HEATMAP_CELL_PIXELS, rows * HEATMAP_CELL_PIXELS);
+    let mut pixels = Vec::with_capacity(width * height * 3);
+    for y in 0..height {
+        for x in 0..width {
+            let (r, c) = (y / HEATMAP_CELL_PIXELS, x / HEATMAP_CELL_PIXELS);
+            pixels.extend(heat_color(
+                peeling.removal_round[r][c],
+                occupied[r][c],
+                peeling.rounds,
+            ));
+        }
+    }
+    (width, height, pixels)
+}
+
+/// Write the removal heatmap as a binary PPM or a PNG, chosen by the file extension.
+///
+/// See: <https://netpbm.sourceforge.net/doc/ppm.html>
+fn write_heatmap(
+    path: &Path,
+    peeling: &Peeling,
+    occupied: &[Vec<bool>],
+) -> anyhow::Result<()> {
+    let (width, height, pixels) = heatmap_pixels(peeling, occupied);
+    let mut out = BufWriter::new(std::fs::File::create(path)?);
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("ppm") => {
+            write!(out, "P6\n{} {}\n255\n", width, height)?;
+            out.write_all(&pixels)?;
+        }
+        Some("png") => {
+            let mut encoder =
+                png::Encoder::new(out, width as u32, height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.write_header()?.write_image_data(&pixels)?;
+        }
+        _ => anyhow::bail!(
+            "unsupported heatmap format (expected .ppm or .png): {}",
+            path.display()
+        ),
+    }
+    Ok(())
 }
 
 fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
     let contents = std::fs::read_to_string("./data/day-04-input.txt")?;
 
     /* Part 1 */
@@ -86,8 +320,15 @@ fn main() -> anyhow::Result<()> {
     println!("Part 1: {}", indices.len());
 
     /* Part 2 */
-    let total_iterative = find_accessible_iterative(occupied)?;
-    println!("Part 2: {}", total_iterative);
+    let peeling = peel(&occupied, ACCESS_THRESHOLD, &DIRS);
+    if args.animate {
+        let delay = std::time::Duration::from_millis(args.delay);
+        animate(&peeling, &occupied, delay, !args.no_color)?;
+    }
+    if let Some(path) = &args.heatmap {
+        write_heatmap(path, &peeling, &occupied)?;
+    }
+    println!("Part 2: {}", peeling.removed);
 
     Ok(())
 }
@@ -125,6 +366,89 @@ mod test {
 .@@@@@@@@.
 @.@.@@@.@.";
         let occupied = construct_occupied(input).unwrap();
-        assert_eq!(find_accessible_iterative(occupied).unwrap(), 43);
+        assert_eq!(peel(&occupied, ACCESS_THRESHOLD, &DIRS).removed, 43);
+    }
+
+    #[test]
+    fn test_peel_rounds_match_rescan() {
+        let input = "..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.";
+        let mut occupied = construct_occupied(input).unwrap();
+        let peeling = peel(&occupied, ACCESS_THRESHOLD, &DIRS);
+
+        // removing each round's accessible rolls in turn must agree with the peeling
+        let mut round = 0;
+        loop {
+            let indices = find_forklift_accessible(&occupied).unwrap();
+            if indices.is_empty() {
+                break;
+            }
+            round += 1;
+            for &(r, c) in &indices {
+                assert_eq!(peeling.removal_round[r][c], Some(round));
+                occupied[r][c] = false;
+            }
+        }
+        assert_eq!(peeling.rounds, round);
+        assert_eq!(peeling.removed, 43);
+    }
+
+    #[test]
+    fn test_peel_custom_neighbourhood() {
+        // orthogonal neighbours only: the centre of the plus survives with a threshold of 2
+        // until its arms are gone, and the arms (1 neighbour each) go first
+        let input = ".@.
+@@@
+.@.";
+        let occupied = construct_occupied(input).unwrap();
+        let orthogonal = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+        let peeling = peel(&occupied, 2, &orthogonal);
+        assert_eq!(peeling.removal_round[0][1], Some(1));
+        assert_eq!(peeling.removal_round[1][1], Some(2));
+        assert_eq!(peeling.rounds, 2);
+        assert_eq!(peeling.removed, 5);
+    }
+
+    #[test]
+    fn test_write_frame_no_color() {
+        let input = "@@@
+@@@
+.@.";
+        let occupied = construct_occupied(input).unwrap();
+        let peeling = peel(&occupied, ACCESS_THRESHOLD, &DIRS);
+        assert_eq!(peeling.rounds, 2);
+
+        let mut out = Vec::new();
+        write_frame(&mut out, &peeling, &occupied, 1, false).unwrap();
+        let frame = String::from_utf8(out).unwrap();
+        assert_eq!(frame, "Round 1/2: 3 removed\nx@x\n@@@\n.x.\n\n");
+
+        let mut out = Vec::new();
+        write_frame(&mut out, &peeling, &occupied, 2, false).unwrap();
+        let frame = String::from_utf8(out).unwrap();
+        assert_eq!(frame, "Round 2/2: 4 removed\n.x.\nxxx\n...\n\n");
+    }
+
+    #[test]
+    fn test_heatmap_pixels() {
+        let occupied = construct_occupied("@.").unwrap();
+        let peeling = peel(&occupied, ACCESS_THRESHOLD, &DIRS);
+        let (width, height, pixels) = heatmap_pixels(&peeling, &occupied);
+        assert_eq!(
+            (width, height),
+            (2 * HEATMAP_CELL_PIXELS, HEATMAP_CELL_PIXELS)
+        );
+        assert_eq!(pixels.len(), width * height * 3);
+        // removed in the only round -> start of the gradient, empty -> black
+        assert_eq!(pixels[..3], [0, 0, 255]);
+        assert_eq!(pixels[(width - 1) * 3..width * 3], [0, 0, 0]);
     }
 }
@@ -21,11 +21,17 @@
  * ```
  */
 
-use std::{
-    cmp::{max, min},
-    collections::HashSet,
-    ops::RangeInclusive,
-};
+use aoc_common::{IntervalSet, sections::sections};
+use clap::Parser;
+use std::{collections::HashSet, ops::RangeInclusive};
+
+/// Solve day 5, optionally explaining the verdict for every ingredient ID.
+#[derive(Parser)]
+struct Args {
+    /// For each ID print the merged range containing it, or the nearest ranges on each side.
+    #[arg(long)]
+    explain: bool,
+}
 
 #[derive(Debug)]
 struct ParsedContent {
@@ -33,98 +39,79 @@ struct ParsedContent {
     ids: HashSet<usize>,
 }
 
-fn parse_contents(contents: &str) -> anyhow::Result<ParsedContent> {
-    let contents = contents.trim();
-    let mut lines_iter = contents.lines();
-    // process ranges
-    let mut ranges = Vec::new();
-    while let Some(line) = lines_iter.next()
-        && !line.is_empty()
-    {
-        if let Some((start, end)) = line.split_once('-') {
-            let range = RangeInclusive::new(
-                start.parse::<usize>()?,
-                end.parse::<usize>()?,
-            );
-            ranges.push(range)
-        } else {
-            anyhow::bail!("invalid range line: '{}'", line)
-        }
+/// Parses a single `start-end` range line.
+fn parse_range(line: &str) -> anyhow::Result<RangeInclusive<usize>> {
+    let (start, end) = line
+        .split_once('-')
+        .ok_or_else(|| anyhow::anyhow!("expected range 'start-end'"))?;
+    let (start, end): (usize, usize) =
+        (start.trim().parse()?, end.trim().parse()?);
+    if start > end {
+        anyhow::bail!("range start {} is after its end {}", start, end)
     }
-
-    // process IDs
-    let ids: HashSet<usize> =
-        lines_iter.map(|id_str| id_str.trim().parse().unwrap()).collect();
-    let parsed = ParsedContent { ranges, ids };
-
-    #[cfg(test)]
-    println!("{:#?}", parsed);
-
-    Ok(parsed)
+    Ok(RangeInclusive::new(start, end))
 }
 
-fn merge_intervals(
-    ranges: &[RangeInclusive<usize>],
-) -> anyhow::Result<Vec<RangeInclusive<usize>>> {
-    if ranges.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    let mut sorted_ranges: Vec<RangeInclusive<usize>> = ranges.to_vec();
-    sorted_ranges.sort_by(|a, b| a.start().cmp(b.start()));
-    let mut merged_ranges: Vec<RangeInclusive<usize>> = Vec::new();
-
-    merged_ranges.push(sorted_ranges[0].clone());
-    let mut last_end = *sorted_ranges[0].end();
-
-    for range in sorted_ranges.iter().skip(1) {
-        if *range.start() > last_end {
-            // no merge conflict
-            last_end = *range.end();
-            merged_ranges.push(range.clone());
-        } else {
-            // merge conflict
-            let last_range =
-                merged_ranges.pop().expect("expected nonempty ranges");
-            let new_start = min(last_range.start(), range.start());
-            let new_end = max(last_range.end(), range.end());
-            let mod_range = RangeInclusive::new(*new_start, *new_end);
-            merged_ranges.push(mod_range);
-            last_end = *new_end;
+fn parse_contents(contents: &str) -> anyhow::Result<ParsedContent> {
+    let sections = sections(contents);
+    let [ranges, ids] = sections.as_slice() else {
+        match sections.get(2) {
+            Some(extra) => anyhow::bail!(
+                "line {}: unexpected third section, expected ranges then IDs",
+                extra.first_line()
+            ),
+            None => anyhow::bail!(
+                "expected a section of ranges and a section of IDs separated by a blank line, found {} section(s)",
+                sections.len()
+            ),
         }
+    };
 
-        #[cfg(test)]
-        #[cfg(debug_assertions)]
-        println!("merged_intervals after '{:?}': {:#?}", range, merged_ranges);
-    }
+    let ranges = ranges.parse_lines(parse_range)?;
+    let ids = ids.parse_lines(|line| line.trim().parse::<usize>())?;
+
+    Ok(ParsedContent { ranges, ids: ids.into_iter().collect() })
+}
 
-    Ok(merged_ranges)
+fn merge_intervals(ranges: &[RangeInclusive<usize>]) -> IntervalSet<usize> {
+    ranges.iter().cloned().collect()
 }
 
 fn find_fresh_ids(parsed: &ParsedContent) -> anyhow::Result<Vec<usize>> {
-    let mut fresh_ids = Vec::new();
-
-    // merge intervals problem
-    let merged_intervals = merge_intervals(&parsed.ranges)?;
-
-    #[cfg(test)]
-    println!("merged ranges: {:#?}", merged_intervals);
-
-    // binary search merge ranges for each ID to see if contained
-    for id in &parsed.ids {
-        let partition_idx =
-            merged_intervals.partition_point(|range| range.end() < id);
-        if partition_idx < merged_intervals.len()
-            && merged_intervals[partition_idx].contains(id)
-        {
-            fresh_ids.push(*id);
+    let merged_intervals = merge_intervals(&parsed.ranges);
+    Ok(parsed
+        .ids
+        .iter()
+        .copied()
+        .filter(|&id| merged_intervals.contains(id))
+        .collect())
+}
+
+/// Describe why `id` is fresh or spoiled in terms of the merged ranges.
+fn explain_id(merged_intervals: &IntervalSet<usize>, id: usize) -> String {
+    let show = |range: Option<RangeInclusive<usize>>| {
+        range.map_or("none".to_string(), |r| {
+            format!("{}-{}", r.start(), r.end())
+        })
+    };
+    match merged_intervals.interval_containing(id) {
+        Some(range) => {
+            format!("{}: fresh, in {}-{}", id, range.start(), range.end())
+        }
+        None => {
+            let (below, above) = merged_intervals.neighbours(id);
+            format!(
+                "{}: spoiled, nearest below {}, nearest above {}",
+                id,
+                show(below),
+                show(above)
+            )
         }
     }
-
-    Ok(fresh_ids)
 }
 
 fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
     let contents = std::fs::read_to_string("./data/day-05-input.txt")?;
 
     /* Part 1 */
@@ -134,10 +121,15 @@ fn main() -> anyhow::Result<()> {
     println!("Part 1: {}", fresh_ids.len());
 
     /* Part 2 */
-    let merged_intervals = merge_intervals(&parsed.ranges)?;
-    let total_fresh_ids: usize =
-        merged_intervals.iter().map(|int| int.clone().count()).sum();
-    println!("Part 2: {}", total_fresh_ids);
+    let merged_intervals = merge_intervals(&parsed.ranges);
+    if args.explain {
+        let mut ids: Vec<usize> = parsed.ids.iter().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            println!("{}", explain_id(&merged_intervals, id));
+        }
+    }
+    println!("Part 2: {}", merged_intervals.len());
 
     Ok(())
 }
@@ -154,10 +146,10 @@ mod test {
             RangeInclusive::new(16, 20),
             RangeInclusive::new(12, 18),
         ];
-        let merged = merge_intervals(&ranges).unwrap();
+        let merged = merge_intervals(&ranges);
         let expected =
             vec![RangeInclusive::new(3, 5), RangeInclusive::new(10, 20)];
-        assert_eq!(merged, expected);
+        assert_eq!(merged.iter().collect::<Vec<_>>(), expected);
     }
 
     #[test]
@@ -177,4 +169,57 @@ mod test {
         let fresh_ids = find_fresh_ids(&parsed).unwrap();
         assert_eq!(fresh_ids.len(), 3)
     }
+
+    #[test]
+    fn test_part2_example() {
+        let input = "3-5
+10-14
+16-20
+12-18
+
+1
+5
+8
+11
+17
+32";
+        let parsed = parse_contents(input).unwrap();
+        assert_eq!(merge_intervals(&parsed.ranges).len(), 14)
+    }
+
+    #[test]
+    fn test_parse_tolerates_crlf_and_trailing_whitespace() {
+        let input = "3-5 \r\n10-14\r\n\r\n1\t\r\n5\r\n\r\n";
+        let parsed = parse_contents(input).unwrap();
+        assert_eq!(parsed.ranges, vec![3..=5, 10..=14]);
+        assert_eq!(parsed.ids, HashSet::from([1, 5]));
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        let err = parse_contents("3-5\n10-14\n\n1\nfive\n").unwrap_err();
+        assert!(err.to_string().starts_with("line 5:"), "{}", err);
+
+        let err = parse_contents("3-5\n14-10\n\n1\n").unwrap_err();
+        assert!(err.to_string().starts_with("line 2:"), "{}", err);
+
+        let err = parse_contents("3-5\n\n1\n\n2\n").unwrap_err();
+        assert!(err.to_string().starts_with("line 5:"), "{}", err);
+
+        assert!(parse_contents("3-5\n10-14\n").is_err());
+    }
+
+    #[test]
+    fn test_explain_id() {
+        let merged = merge_intervals(&[3..=5, 10..=14, 16..=20, 12..=18]);
+        assert_eq!(explain_id(&merged, 5), "5: fresh, in 3-5");
+        assert_eq!(
+            explain_id(&merged, 8),
+            "8: spoiled, nearest below 3-5, nearest above 10-20"
+        );
+        assert_eq!(
+            explain_id(&merged, 1),
+            "1: spoiled, nearest below none, nearest above 3-5"
+        );
+    }
 }
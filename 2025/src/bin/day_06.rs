@@ -1,138 +1,402 @@
-fn normal_num_parse(contents: &str) -> anyhow::Result<Vec<Vec<usize>>> {
-    let rows = contents.lines().count();
-    let num_grid: Vec<Vec<usize>> = contents
-        .lines()
-        .take(rows - 1) // skip last row
-        .map(|line| {
-            line.split_whitespace()
-                .map(|s| s.parse::<usize>().unwrap())
-                .collect()
-        })
-        .collect();
-    Ok(num_grid)
-}
-
-fn part_1(contents: &str) -> anyhow::Result<Vec<usize>> {
-    let num_grid = normal_num_parse(contents)?;
-    let mut results = Vec::new();
-    for (col, op) in
-        contents.lines().last().unwrap().split_whitespace().enumerate()
-    {
-        match op {
-            "+" => results
-                .push(num_grid.iter().fold(0, |acc, row| acc + row[col])),
-            "*" => results
-                .push(num_grid.iter().fold(1, |acc, row| acc * row[col])),
-            _ => anyhow::bail!("unsupported operation: '{}'", op),
+use clap::Parser;
+use std::{ops::Range, str::FromStr};
+
+/// Operator written under a problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Min,
+    Max,
+    Pow,
+}
+
+/// Every supported operator with the symbol used for it on a worksheet.
+const OPERATORS: [(&str, Operator); 7] = [
+    ("+", Operator::Add),
+    ("-", Operator::Sub),
+    ("*", Operator::Mul),
+    ("/", Operator::Div),
+    ("min", Operator::Min),
+    ("max", Operator::Max),
+    ("^", Operator::Pow),
+];
+
+impl FromStr for Operator {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OPERATORS
+            .iter()
+            .find(|(symbol, _)| *symbol == s)
+            .map(|&(_, op)| op)
+            .ok_or_else(|| anyhow::anyhow!("unsupported operation: '{}'", s))
+    }
+}
+
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (symbol, _) = OPERATORS
+            .iter()
+            .find(|(_, op)| op == self)
+            .expect("every operator is registered");
+        write!(f, "{}", symbol)
+    }
+}
+
+/// Why an operator couldn't combine two operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArithError {
+    /// Result above `i128::MAX`
+    Overflow,
+    /// Result below `i128::MIN`
+    Underflow,
+    DivisionByZero,
+    NegativeExponent,
+}
+
+impl std::fmt::Display for ArithError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            ArithError::Overflow => "overflow",
+            ArithError::Underflow => "underflow",
+            ArithError::DivisionByZero => "division by zero",
+            ArithError::NegativeExponent => "negative exponent",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl Operator {
+    /// Combine two operands. Results may go negative, as long as they fit in an `i128`.
+    fn checked_apply(self, lhs: i128, rhs: i128) -> Result<i128, ArithError> {
+        // the side of the range a result that doesn't fit fell off
+        let out_of_range = |negative: bool| {
+            if negative { ArithError::Underflow } else { ArithError::Overflow }
+        };
+        match self {
+            Operator::Add => lhs.checked_add(rhs).ok_or(out_of_range(rhs < 0)),
+            Operator::Sub => lhs.checked_sub(rhs).ok_or(out_of_range(rhs > 0)),
+            Operator::Mul => lhs
+                .checked_mul(rhs)
+                .ok_or(out_of_range((lhs < 0) != (rhs < 0))),
+            Operator::Div if rhs == 0 => Err(ArithError::DivisionByZero),
+            // only i128::MIN / -1 doesn't fit
+            Operator::Div => lhs.checked_div(rhs).ok_or(ArithError::Overflow),
+            Operator::Min => Ok(lhs.min(rhs)),
+            Operator::Max => Ok(lhs.max(rhs)),
+            Operator::Pow if rhs < 0 => Err(ArithError::NegativeExponent),
+            // these stay small for any exponent, even one too large for `checked_pow`
+            Operator::Pow if lhs == 0 => Ok(i128::from(rhs == 0)),
+            Operator::Pow if lhs == 1 => Ok(1),
+            Operator::Pow if lhs == -1 => {
+                Ok(if rhs % 2 == 0 { 1 } else { -1 })
+            }
+            Operator::Pow => {
+                let negative = lhs < 0 && rhs % 2 == 1;
+                u32::try_from(rhs)
+                    .ok()
+                    .and_then(|exp| lhs.checked_pow(exp))
+                    .ok_or(out_of_range(negative))
+            }
         }
     }
-    Ok(results)
 }
 
-fn right_left_columnar_parse(
-    contents: &str,
-) -> anyhow::Result<Vec<Vec<usize>>> {
-    let rows = contents.lines().count();
-    let lines: Vec<&str> = contents.lines().take(rows - 1).collect(); // skip last
+/// Order in which the character columns of a problem are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    LeftToRight,
+    RightToLeft,
+}
 
-    // Find max length
-    let max_len = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+/// A math worksheet: rows of digits above a row of operators, with problems separated by columns
+/// of spaces.
+///
+/// Every line must reach into the last problem. Past that point a line may stop short, since
+/// editors strip trailing whitespace: the missing columns are read as blanks of the last problem
+/// or separators after it. A line that stops before the last problem is ragged and rejected.
+/// Columns are counted in bytes, so tabs, control characters and non-ASCII text are rejected too.
+///
+/// Example:
+///
+/// ```txt
+/// 123 328  51 64
+///  45 64  387 23
+///   6 98  215 314
+/// *   +   *   +
+/// ```
+#[derive(Debug)]
+struct Worksheet {
+    /// Digit rows, filled out with spaces to a common width
+    rows: Vec<Vec<u8>>,
+    /// Character columns and operator of each problem, left to right
+    problems: Vec<(Range<usize>, Operator)>,
+}
 
-    let mut problems: Vec<Vec<usize>> = Vec::new();
-    let mut current_number_digits: Vec<Vec<char>> = Vec::new();
+/// A single problem of a [`Worksheet`].
+#[derive(Debug)]
+struct Problem<'a> {
+    rows: &'a [Vec<u8>],
+    /// Character columns of the problem
+    columns: Range<usize>,
+    operator: Operator,
+}
 
-    // Process character columns from right to left
-    for col_idx in (0..max_len).rev() {
-        let mut column_chars: Vec<char> = Vec::new();
+impl FromStr for Worksheet {
+    type Err = anyhow::Error;
 
-        // Collect characters at this position from all rows
-        for line in &lines {
-            if col_idx < line.len() {
-                column_chars.push(line.chars().nth(col_idx).unwrap());
-            } else {
-                column_chars.push(' ');
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s
+            .lines()
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect();
+        // skip surrounding blank lines, but keep line numbers relative to the input
+        let (Some(first), Some(last)) = (
+            lines.iter().position(|line| !line.trim().is_empty()),
+            lines.iter().rposition(|line| !line.trim().is_empty()),
+        ) else {
+            anyhow::bail!("empty worksheet")
+        };
+        let line_number = |idx: usize| first + idx + 1;
+        let lines = &lines[first..=last];
+        let Some((op_line, digit_lines)) = lines.split_last() else {
+            anyhow::bail!("empty worksheet")
+        };
+        if digit_lines.is_empty() {
+            anyhow::bail!("worksheet has an operator row but no numbers")
+        }
+
+        // columns are addressed by byte, so anything wider than one byte (or a tab) would
+        // misalign the rows
+        for (idx, line) in lines.iter().enumerate() {
+            if let Some((col, ch)) = line
+                .char_indices()
+                .find(|(_, ch)| !ch.is_ascii() || ch.is_ascii_control())
+            {
+                anyhow::bail!(
+                    "line {}: {:?} at column {} breaks column alignment",
+                    line_number(idx),
+                    ch,
+                    col + 1
+                )
             }
         }
 
-        // If all spaces --> problem separator
-        let is_separator = column_chars.iter().all(|&c| c == ' ');
-
-        if is_separator {
-            // End of a problem - convert accumulated digits to numbers
-            if !current_number_digits.is_empty() {
-                let mut numbers = Vec::new();
-                for digit_group in &current_number_digits {
-                    if !digit_group.is_empty() {
-                        let num_str: String = digit_group.iter().collect();
-                        numbers.push(num_str.parse::<usize>()?);
-                    }
-                }
-                numbers.reverse(); // Since we built them backwards
-                problems.push(numbers);
-                current_number_digits.clear();
+        // editors strip trailing whitespace, so read shorter lines as blank on the right for now
+        // and check they reach the last problem once the problems are known
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let pad = |line: &str| {
+            let mut row = line.as_bytes().to_vec();
+            row.resize(width, b' ');
+            row
+        };
+        let rows: Vec<Vec<u8>> = digit_lines.iter().map(|l| pad(l)).collect();
+        let op_row = pad(op_line);
+
+        for (idx, row) in rows.iter().enumerate() {
+            if let Some(col) =
+                row.iter().position(|&b| b != b' ' && !b.is_ascii_digit())
+            {
+                anyhow::bail!(
+                    "line {}: unexpected character '{}' at column {} in number row",
+                    line_number(idx),
+                    row[col] as char,
+                    col + 1
+                )
             }
-        } else {
-            // Part of a number - collect digits from this column
-            let mut digit_group = Vec::new();
-            for &ch in &column_chars {
-                if ch.is_ascii_digit() {
-                    digit_group.push(ch);
-                }
+        }
+
+        // split once into blocks of non-separator columns
+        let is_separator = |col: usize| {
+            op_row[col] == b' ' && rows.iter().all(|row| row[col] == b' ')
+        };
+        let mut blocks = Vec::new();
+        let mut col = 0;
+        while col < width {
+            if is_separator(col) {
+                col += 1;
+                continue;
             }
-            if !digit_group.is_empty() {
-                current_number_digits.push(digit_group);
+            let start = col;
+            while col < width && !is_separator(col) {
+                col += 1;
             }
+            blocks.push(start..col);
         }
-    }
-
-    // Last problem (leftmost)
-    if !current_number_digits.is_empty() {
-        let mut numbers = Vec::new();
-        for digit_group in &current_number_digits {
-            if !digit_group.is_empty() {
-                let num_str: String = digit_group.iter().collect();
-                numbers.push(num_str.parse::<usize>()?);
+        if let Some(last) = blocks.last() {
+            for (idx, line) in lines.iter().enumerate() {
+                if line.len() <= last.start {
+                    anyhow::bail!(
+                        "line {}: ragged line, ends at column {} before the last problem at column {}",
+                        line_number(idx),
+                        line.len(),
+                        last.start + 1
+                    )
+                }
             }
         }
-        numbers.reverse();
-        problems.push(numbers);
-    }
 
-    #[cfg(test)]
-    println!("parsed grid: {:?}", problems);
+        let problems = blocks
+            .into_iter()
+            .map(|columns| {
+                let token = std::str::from_utf8(&op_row[columns.clone()])?;
+                let mut tokens = token.split_whitespace();
+                let op = match (tokens.next(), tokens.next()) {
+                    (Some(op), None) => op.parse::<Operator>(),
+                    (None, _) => Err(anyhow::anyhow!("missing operator")),
+                    (Some(_), Some(_)) => Err(anyhow::anyhow!(
+                        "more than one operator: '{}'",
+                        token.trim()
+                    )),
+                };
+                let op = op.map_err(|e| {
+                    anyhow::anyhow!(
+                        "line {}: problem at column {}: {}",
+                        line_number(lines.len() - 1),
+                        columns.start + 1,
+                        e
+                    )
+                })?;
+                Ok((columns, op))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Worksheet { rows, problems })
+    }
+}
 
-    Ok(problems)
+impl Worksheet {
+    /// Problems from left to right.
+    fn problems(&self) -> impl DoubleEndedIterator<Item = Problem<'_>> {
+        self.problems.iter().map(|(columns, operator)| Problem {
+            rows: &self.rows,
+            columns: columns.clone(),
+            operator: *operator,
+        })
+    }
 }
 
-fn part_2(contents: &str) -> anyhow::Result<Vec<usize>> {
-    let num_grid = right_left_columnar_parse(contents)?;
-    let mut results = Vec::new();
+impl Problem<'_> {
+    /// Left fold of the operator over `numbers`, in the order given.
+    ///
+    /// # Errors
+    ///
+    /// If the problem has no numbers, or a step divides by zero or leaves the `i128` range.
+    fn evaluate(&self, numbers: &[usize]) -> anyhow::Result<i128> {
+        let column = self.columns.start + 1;
+        let Some((&first, rest)) = numbers.split_first() else {
+            anyhow::bail!("problem at column {}: no numbers", column)
+        };
+        rest.iter().try_fold(first as i128, |acc, &number| {
+            self.operator.checked_apply(acc, number as i128).map_err(|e| {
+                anyhow::anyhow!(
+                    "problem at column {}: {} evaluating {} {} {}",
+                    column,
+                    e,
+                    acc,
+                    self.operator,
+                    number
+                )
+            })
+        })
+    }
 
-    let mut ops: Vec<&str> =
-        contents.lines().last().unwrap().split_whitespace().collect();
-    ops.reverse();
+    /// Numbers written left to right on each row, top to bottom.
+    fn row_numbers(&self) -> anyhow::Result<Vec<usize>> {
+        self.rows
+            .iter()
+            .map(|row| std::str::from_utf8(&row[self.columns.clone()]))
+            .filter_map(|digits| match digits {
+                Ok(digits) if digits.trim().is_empty() => None,
+                Ok(digits) => Some(digits.trim().parse().map_err(Into::into)),
+                Err(e) => Some(Err(e.into())),
+            })
+            .collect()
+    }
 
-    for (col, op) in ops.iter().enumerate() {
-        match *op {
-            "+" => results.push(num_grid[col].iter().sum()),
-            "*" => results.push(num_grid[col].iter().product()),
-            _ => anyhow::bail!("unsupported operation: '{}'", op),
-        }
+    /// Numbers written top to bottom in each character column, read in `direction`.
+    fn column_numbers(
+        &self,
+        direction: Direction,
+    ) -> anyhow::Result<Vec<usize>> {
+        let columns: Box<dyn Iterator<Item = usize>> = match direction {
+            Direction::LeftToRight => Box::new(self.columns.clone()),
+            Direction::RightToLeft => Box::new(self.columns.clone().rev()),
+        };
+        columns
+            .map(|col| {
+                let digits: String = self
+                    .rows
+                    .iter()
+                    .map(|row| row[col] as char)
+                    .filter(char::is_ascii_digit)
+                    .collect();
+                digits
+            })
+            .filter(|digits| !digits.is_empty())
+            .map(|digits| Ok(digits.parse()?))
+            .collect()
     }
+}
+
+fn part_1(contents: &str) -> anyhow::Result<Vec<i128>> {
+    let worksheet: Worksheet = contents.parse()?;
+    worksheet
+        .problems()
+        .map(|problem| problem.evaluate(&problem.row_numbers()?))
+        .collect()
+}
 
-    Ok(results)
+/// Cephalopod math is read one number per character column, right to left as the puzzle has
+/// it: problems in `direction`, and the columns of each problem too.
+fn part_2(contents: &str, direction: Direction) -> anyhow::Result<Vec<i128>> {
+    let worksheet: Worksheet = contents.parse()?;
+    let problems: Box<dyn Iterator<Item = Problem>> = match direction {
+        Direction::LeftToRight => Box::new(worksheet.problems()),
+        Direction::RightToLeft => Box::new(worksheet.problems().rev()),
+    };
+    problems
+        .map(|problem| {
+            let numbers = problem.column_numbers(direction)?;
+            problem.evaluate(&numbers)
+        })
+        .collect()
+}
+
+/// Grand total of all problem results.
+fn checked_total(results: &[i128]) -> anyhow::Result<i128> {
+    results.iter().try_fold(0i128, |acc, &result| {
+        acc.checked_add(result)
+            .ok_or_else(|| anyhow::anyhow!("grand total overflows i128"))
+    })
+}
+
+#[derive(Parser)]
+struct Args {
+    /// Read the part 2 columns left to right instead of right to left.
+    #[arg(long)]
+    left_to_right: bool,
 }
 
 fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let direction = if args.left_to_right {
+        Direction::LeftToRight
+    } else {
+        Direction::RightToLeft
+    };
     let contents = std::fs::read_to_string("./data/day-06-input.txt")?;
 
     /* Part 1 */
     let applied_aps = part_1(&contents)?;
-    println!("Part 1: {}", applied_aps.iter().sum::<usize>());
+    println!("Part 1: {}", checked_total(&applied_aps)?);
 
     /* Part 2 */
-    let applied_aps = part_2(&contents)?;
-    println!("Part 2: {}", applied_aps.iter().sum::<usize>());
+    let applied_aps = part_2(&contents, direction)?;
+    println!("Part 2: {}", checked_total(&applied_aps)?);
 
     Ok(())
 }
@@ -149,7 +413,7 @@ mod test {
 *   +   *   + ";
         let ops = part_1(input).unwrap();
         assert_eq!(ops, vec![33210, 490, 4243455, 401]);
-        let sum = ops.iter().sum::<usize>();
+        let sum = ops.iter().sum::<i128>();
         assert_eq!(sum, 4277556)
     }
 
@@ -159,9 +423,121 @@ mod test {
  45 64  387 23
   6 98  215 314
 *   +   *   + ";
-        let ops = part_2(input).unwrap();
+        let ops = part_2(input, Direction::RightToLeft).unwrap();
         assert_eq!(ops, vec![1058, 3253600, 625, 8544]);
-        let sum = ops.iter().sum::<usize>();
-        assert_eq!(sum, 3263827)
+        let sum = ops.iter().sum::<i128>();
+        assert_eq!(sum, 3263827);
+        // + and * don't care which way the columns are read
+        let ops = part_2(input, Direction::LeftToRight).unwrap();
+        assert_eq!(ops, vec![8544, 625, 3253600, 1058]);
+    }
+
+    #[test]
+    fn test_worksheet_blocks() {
+        let input = "123 328  51 64
+ 45 64  387 23
+  6 98  215 314
+*   +   *   + ";
+        let worksheet: Worksheet = input.parse().unwrap();
+        let problems: Vec<Problem> = worksheet.problems().collect();
+        assert_eq!(problems.len(), 4);
+        assert_eq!(problems[2].columns, 8..11);
+        assert_eq!(problems[2].operator, Operator::Mul);
+        assert_eq!(problems[2].row_numbers().unwrap(), vec![51, 387, 215]);
+        assert_eq!(
+            problems[2].column_numbers(Direction::LeftToRight).unwrap(),
+            vec![32, 581, 175]
+        );
+        assert_eq!(
+            problems[2].column_numbers(Direction::RightToLeft).unwrap(),
+            vec![175, 581, 32]
+        );
+    }
+
+    #[test]
+    fn test_worksheet_errors() {
+        let err = "12 3\n+  ?".parse::<Worksheet>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: problem at column 4: unsupported operation: '?'"
+        );
+
+        let err = "12\t3\n+  *".parse::<Worksheet>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: '\\t' at column 3 breaks column alignment"
+        );
+        // lines may stop short inside the last problem, not before it
+        let worksheet: Worksheet = "12 3\n1  4\n+  *".parse().unwrap();
+        assert_eq!(worksheet.rows[1], b"1  4".to_vec());
+        let worksheet: Worksheet = "12 34\n1  4\n+  *".parse().unwrap();
+        assert_eq!(worksheet.rows[1], b"1  4 ".to_vec());
+        let err = "12 3\n1\n+  *".parse::<Worksheet>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: ragged line, ends at column 1 before the last problem at column 4"
+        );
+
+        let err = "12 3\n1x 4\n+  *".parse::<Worksheet>().unwrap_err();
+        assert!(err.to_string().starts_with("line 2:"), "{}", err);
+
+        let err = "12 3\n   *".parse::<Worksheet>().unwrap_err();
+        assert!(err.to_string().contains("missing operator"), "{}", err);
+    }
+
+    #[test]
+    fn test_extended_operators() {
+        // the last row has no number for the last problem, so keeps its blanks
+        let input = "100 8 3   12  2
+ 30 2 9   5   10
+  5 2 4   7    \n-   / min max ^";
+        assert_eq!(part_1(input).unwrap(), vec![65, 2, 3, 12, 1024]);
+    }
+
+    #[test]
+    fn test_overflow_names_problem_column() {
+        let input = "1 99999999999
+2  9999999999
++ ^";
+        let err = part_1(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "problem at column 3: overflow evaluating 99999999999 ^ 9999999999"
+        );
+
+        // going below zero is fine, leaving the i128 range is not
+        let input = "1 3
+2 5
++ -";
+        assert_eq!(part_1(input).unwrap(), vec![3, -2]);
+
+        let input = "1 3
+2 0
++ /";
+        let err = part_1(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "problem at column 3: division by zero evaluating 3 / 0"
+        );
+
+        let op = Operator::Sub;
+        assert_eq!(op.checked_apply(i128::MIN, 1), Err(ArithError::Underflow));
+        assert_eq!(op.checked_apply(i128::MAX, -1), Err(ArithError::Overflow));
+        let op = Operator::Pow;
+        assert_eq!(op.checked_apply(-3, 81), Err(ArithError::Underflow));
+        assert_eq!(op.checked_apply(-3, 82), Err(ArithError::Overflow));
+        assert_eq!(op.checked_apply(1, 5_000_000_000), Ok(1));
+        assert_eq!(op.checked_apply(-1, 5_000_000_001), Ok(-1));
+        assert_eq!(op.checked_apply(-1, 5_000_000_000), Ok(1));
+        assert_eq!(op.checked_apply(0, 5_000_000_000), Ok(0));
+        assert_eq!(op.checked_apply(0, 0), Ok(1));
+        assert_eq!(
+            op.checked_apply(2, 5_000_000_000),
+            Err(ArithError::Overflow)
+        );
+        let input = "1
+5000000000
+^";
+        assert_eq!(part_1(input).unwrap(), vec![1]);
     }
 }
@@ -1,76 +1,622 @@
-use std::collections::HashSet;
-
-fn compute_total_beam_splits(
-    contents: &str,
-) -> anyhow::Result<(usize, usize)> {
-    let contents = contents.trim();
-    let mut curr_beam_indices: HashSet<usize> = HashSet::new();
-    let starting_idx = contents
-        .lines()
-        .next()
-        .unwrap()
-        .find('S')
-        .expect("no 'S' on first line");
-
-    curr_beam_indices.insert(starting_idx);
-
-    let mut total_splits = 0;
-
-    // beam_counts[pos] -> number of timelines with beam at that position
-    let cols = contents.lines().next().unwrap().chars().count();
-    let mut beam_counts = vec![0; cols];
-    beam_counts[starting_idx] = 1;
-
-    for line in contents.lines().skip(1) {
-        let splitter_indices: HashSet<usize> =
-            line.match_indices("^").map(|(idx, _)| idx).collect();
-
-        if splitter_indices.is_empty() {
-            // no splitters on this line
-            continue;
-        }
-
-        //#[cfg(test)]
-        //println!(
-        //    "found splitters at: {:?}; total splits: {}",
-        //    splitter_indices, total_splits
-        //);
-
-        // find intersecting splitters
-        let intersecting_splitters: HashSet<usize> = curr_beam_indices
-            .intersection(&splitter_indices)
-            .copied()
+use clap::Parser;
+use std::str::FromStr;
+
+/// Solve day 7, optionally showing where the timelines go.
+#[derive(Parser)]
+struct Args {
+    /// List the first N timelines with the side taken at each splitter.
+    #[arg(long, value_name = "N")]
+    paths: Option<usize>,
+
+    /// List N timelines sampled uniformly at random instead.
+    #[arg(long, value_name = "N", conflicts_with = "paths")]
+    sample: Option<usize>,
+
+    /// Seed for --sample.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Draw the manifold with the listed timelines (or every beam) traced in.
+    #[arg(long)]
+    render: bool,
+
+    /// Print a heatmap of how many timelines pass through each cell.
+    #[arg(long)]
+    heatmap: bool,
+}
+
+/// Contents of a single cell of the manifold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    /// `.` beams pass straight through
+    Empty,
+    /// `S` where the beam enters, heading down
+    Start,
+    /// `^` splits a vertical beam into two beams continuing from the cells either side of it,
+    /// horizontal beams pass through
+    Splitter,
+    /// `/` reflects the beam by 90 degrees
+    MirrorSlash,
+    /// `\` reflects the beam by 90 degrees
+    MirrorBackslash,
+    /// `#` stops the beam
+    Absorber,
+    /// `E` lets the beam leave the manifold
+    Exit,
+}
+
+impl TryFrom<char> for Cell {
+    type Error = anyhow::Error;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        Ok(match ch {
+            '.' => Cell::Empty,
+            'S' => Cell::Start,
+            '^' => Cell::Splitter,
+            '/' => Cell::MirrorSlash,
+            '\\' => Cell::MirrorBackslash,
+            '#' => Cell::Absorber,
+            'E' => Cell::Exit,
+            _ => anyhow::bail!("unexpected cell character: '{}'", ch),
+        })
+    }
+}
+
+impl From<Cell> for char {
+    fn from(cell: Cell) -> Self {
+        match cell {
+            Cell::Empty => '.',
+            Cell::Start => 'S',
+            Cell::Splitter => '^',
+            Cell::MirrorSlash => '/',
+            Cell::MirrorBackslash => '\\',
+            Cell::Absorber => '#',
+            Cell::Exit => 'E',
+        }
+    }
+}
+
+/// Direction a beam is travelling in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] =
+        [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+
+    fn reflect(self, mirror: Cell) -> Direction {
+        match (mirror, self) {
+            (Cell::MirrorSlash, Direction::Right) => Direction::Up,
+            (Cell::MirrorSlash, Direction::Up) => Direction::Right,
+            (Cell::MirrorSlash, Direction::Left) => Direction::Down,
+            (Cell::MirrorSlash, Direction::Down) => Direction::Left,
+            (Cell::MirrorBackslash, Direction::Right) => Direction::Down,
+            (Cell::MirrorBackslash, Direction::Down) => Direction::Right,
+            (Cell::MirrorBackslash, Direction::Left) => Direction::Up,
+            (Cell::MirrorBackslash, Direction::Up) => Direction::Left,
+            _ => self,
+        }
+    }
+}
+
+/// A beam that has just entered cell (`row`, `col`) heading in `dir`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Beam {
+    row: usize,
+    col: usize,
+    dir: Direction,
+}
+
+/// Where a beam goes after passing through a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Next {
+    /// The beam enters another cell of the grid
+    Beam(Beam),
+    /// The beam leaves the grid or an exit cell
+    Exit,
+    /// The beam is stopped by an absorber
+    Absorbed,
+}
+
+/// Tachyon manifold with a single beam entering at `S`.
+///
+/// Example:
+///
+/// ```txt
+/// .......S.......
+/// ...............
+/// .......^.......
+/// ...............
+/// ......^.^......
+/// ```
+#[derive(Debug)]
+struct BeamField {
+    cells: Vec<Vec<Cell>>,
+    /// Beam leaving the start cell
+    start: Beam,
+}
+
+impl FromStr for BeamField {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cells: Vec<Vec<Cell>> = s
+            .trim()
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                line.trim()
+                    .chars()
+                    .map(Cell::try_from)
+                    .collect::<anyhow::Result<Vec<Cell>>>()
+                    .map_err(|e| anyhow::anyhow!("line {}: {}", row + 1, e))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let cols = cells.first().map_or(0, |row| row.len());
+        if let Some(row) = cells.iter().position(|row| row.len() != cols) {
+            anyhow::bail!(
+                "line {}: expected {} cells, found {}",
+                row + 1,
+                cols,
+                cells[row].len()
+            )
+        }
+
+        let mut starts = cells.iter().enumerate().flat_map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .filter(|&(_, &cell)| cell == Cell::Start)
+                .map(move |(col, _)| (row, col))
+        });
+        let Some((row, col)) = starts.next() else {
+            anyhow::bail!("no 'S' in the manifold")
+        };
+        if let Some((other_row, other_col)) = starts.next() {
+            anyhow::bail!(
+                "more than one 'S': line {} column {} and line {} column {}",
+                row + 1,
+                col + 1,
+                other_row + 1,
+                other_col + 1
+            )
+        }
+
+        Ok(BeamField { cells, start: Beam { row, col, dir: Direction::Down } })
+    }
+}
+
+impl BeamField {
+    fn rows(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn cols(&self) -> usize {
+        self.cells[0].len()
+    }
+
+    /// The cell `offset` away from (`row`, `col`), if it lies inside the grid.
+    fn neighbour(
+        &self,
+        row: usize,
+        col: usize,
+        (dr, dc): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let row = row.checked_add_signed(dr).filter(|&r| r < self.rows())?;
+        let col = col.checked_add_signed(dc).filter(|&c| c < self.cols())?;
+        Some((row, col))
+    }
+
+    /// Beam entering the cell `offset` away from (`row`, `col`), or an exit when that is
+    /// outside the grid.
+    fn enter(
+        &self,
+        row: usize,
+        col: usize,
+        offset: (isize, isize),
+        dir: Direction,
+    ) -> Next {
+        match self.neighbour(row, col, offset) {
+            Some((row, col)) => Next::Beam(Beam { row, col, dir }),
+            None => Next::Exit,
+        }
+    }
+
+    /// Where `beam` goes after interacting with the cell it is in. Splitters produce two
+    /// beams, everything else one.
+    fn step(&self, beam: Beam) -> [Option<Next>; 2] {
+        let Beam { row, col, dir } = beam;
+        match self.cells[row][col] {
+            Cell::Absorber => [Some(Next::Absorbed), None],
+            Cell::Exit => [Some(Next::Exit), None],
+            Cell::Splitter if dir.is_vertical() => [
+                Some(self.enter(row, col, Direction::Left.delta(), dir)),
+                Some(self.enter(row, col, Direction::Right.delta(), dir)),
+            ],
+            cell @ (Cell::MirrorSlash | Cell::MirrorBackslash) => {
+                let dir = dir.reflect(cell);
+                [Some(self.enter(row, col, dir.delta(), dir)), None]
+            }
+            Cell::Empty | Cell::Start | Cell::Splitter => {
+                [Some(self.enter(row, col, dir.delta(), dir)), None]
+            }
+        }
+    }
+
+    /// Dense index of a beam state, for per-state tables.
+    fn state_idx(&self, beam: Beam) -> usize {
+        let dir = Direction::ALL.iter().position(|&d| d == beam.dir).unwrap();
+        (beam.row * self.cols() + beam.col) * Direction::ALL.len() + dir
+    }
+
+    /// Every beam state reachable from `S`, each once. Beams that loop are only followed until
+    /// they repeat a state.
+    fn reachable_beams(&self) -> Vec<Beam> {
+        let mut seen = vec![false; self.rows() * self.cols() * 4];
+        let mut reached = Vec::new();
+        let mut stack = vec![self.start];
+        while let Some(beam) = stack.pop() {
+            let idx = self.state_idx(beam);
+            if seen[idx] {
+                continue;
+            }
+            seen[idx] = true;
+            reached.push(beam);
+            for next in self.step(beam).into_iter().flatten() {
+                if let Next::Beam(next) = next {
+                    stack.push(next);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Number of distinct splitters that split a beam.
+    fn count_splits(&self) -> usize {
+        let mut split = vec![vec![false; self.cols()]; self.rows()];
+        for beam in self.reachable_beams() {
+            if self.cells[beam.row][beam.col] == Cell::Splitter
+                && beam.dir.is_vertical()
+            {
+                split[beam.row][beam.col] = true;
+            }
+        }
+        split.iter().flatten().filter(|&&split| split).count()
+    }
+
+    /// Number of timelines leaving every reachable beam state, plus those states in topological
+    /// order. A timeline is a distinct path the beam can take until it leaves the manifold or is
+    /// absorbed.
+    ///
+    /// # Errors
+    ///
+    /// If a beam can loop forever, since there are then infinitely many timelines.
+    fn timeline_table(&self) -> anyhow::Result<TimelineTable> {
+        #[derive(Clone, Copy)]
+        enum Mark {
+            Unvisited,
+            OnPath,
+            Done(u128),
+        }
+
+        // iterative post-order DFS, a state met again while still on the path is a cycle
+        let mut marks = vec![Mark::Unvisited; self.rows() * self.cols() * 4];
+        let mut finished = Vec::new();
+        let mut stack = vec![(self.start, false)];
+        while let Some((beam, expanded)) = stack.pop() {
+            let idx = self.state_idx(beam);
+            let nexts = self.step(beam);
+            if expanded {
+                let mut count: u128 = 0;
+                for next in nexts.into_iter().flatten() {
+                    let paths = match next {
+                        Next::Beam(next) => {
+                            match marks[self.state_idx(next)] {
+                                Mark::Done(paths) => paths,
+                                _ => unreachable!("successor finished first"),
+                            }
+                        }
+                        Next::Exit | Next::Absorbed => 1,
+                    };
+                    count = count.checked_add(paths).ok_or_else(|| {
+                        anyhow::anyhow!("timeline count overflows u128")
+                    })?;
+                }
+                marks[idx] = Mark::Done(count);
+                finished.push(beam);
+                continue;
+            }
+            match marks[idx] {
+                Mark::Done(_) => continue,
+                Mark::OnPath => anyhow::bail!(
+                    "beam loops forever through line {} column {} heading {:?}",
+                    beam.row + 1,
+                    beam.col + 1,
+                    beam.dir
+                ),
+                Mark::Unvisited => {}
+            }
+            marks[idx] = Mark::OnPath;
+            stack.push((beam, true));
+            for next in nexts.into_iter().flatten() {
+                // successors still on the path are caught as cycles when popped
+                if let Next::Beam(next) = next
+                    && !matches!(marks[self.state_idx(next)], Mark::Done(_))
+                {
+                    stack.push((next, false));
+                }
+            }
+        }
+
+        let remaining = marks
+            .into_iter()
+            .map(|mark| match mark {
+                Mark::Done(count) => count,
+                _ => 0,
+            })
             .collect();
+        finished.reverse();
+        Ok(TimelineTable { remaining, order: finished })
+    }
 
-        for &idx in &intersecting_splitters {
-            curr_beam_indices.remove(&idx);
+    /// Number of timelines from `S`. Each split doubles the timelines, so the count grows
+    /// exponentially with the number of splitters on a path.
+    ///
+    /// # Errors
+    ///
+    /// If a beam can loop forever, since there are then infinitely many timelines.
+    fn count_timelines(&self) -> anyhow::Result<u128> {
+        Ok(self.timeline_table()?.remaining[self.state_idx(self.start)])
+    }
+
+    /// cell_timelines[r][c] -> number of timelines with a beam in cell (r, c).
+    ///
+    /// Same propagation as pushing per-row beam counts down the manifold, run over the beam
+    /// states in topological order so it also works for beams moving sideways or up.
+    fn cell_timelines(&self) -> anyhow::Result<Vec<Vec<u128>>> {
+        let table = self.timeline_table()?;
+        let mut arriving = vec![0u128; table.remaining.len()];
+        arriving[self.state_idx(self.start)] = 1;
+        let mut cells = vec![vec![0u128; self.cols()]; self.rows()];
+        for &beam in &table.order {
+            let count = arriving[self.state_idx(beam)];
+            cells[beam.row][beam.col] += count;
+            for next in self.step(beam).into_iter().flatten() {
+                if let Next::Beam(next) = next {
+                    arriving[self.state_idx(next)] += count;
+                }
+            }
         }
+        Ok(cells)
+    }
 
-        let mut new_active: HashSet<usize> = HashSet::new();
-        for &splitter_idx in &intersecting_splitters {
-            total_splits += 1;
-            new_active.insert(splitter_idx - 1);
-            new_active.insert(splitter_idx + 1);
+    /// Every timeline in turn, generated lazily depth first, taking the left-hand beam of a
+    /// split first.
+    ///
+    /// # Errors
+    ///
+    /// If a beam can loop forever.
+    fn timelines(&self) -> anyhow::Result<Timelines<'_>> {
+        // a cycle would make the enumeration never end
+        self.timeline_table()?;
+        Ok(Timelines {
+            field: self,
+            path: Vec::new(),
+            pending: vec![(0, Next::Beam(self.start))],
+        })
+    }
 
-            let count = beam_counts[splitter_idx];
-            beam_counts[splitter_idx] = 0;
-            beam_counts[splitter_idx - 1] += count;
-            beam_counts[splitter_idx + 1] += count;
+    /// `n` timelines drawn uniformly at random (with replacement) from all of them.
+    ///
+    /// # Errors
+    ///
+    /// If a beam can loop forever.
+    fn sample_timelines(
+        &self,
+        n: usize,
+        rng: &mut XorShift,
+    ) -> anyhow::Result<Vec<Timeline>> {
+        let table = self.timeline_table()?;
+        let paths = |next: Next| match next {
+            Next::Beam(beam) => table.remaining[self.state_idx(beam)],
+            Next::Exit | Next::Absorbed => 1,
+        };
+
+        let mut sampled = Vec::with_capacity(n);
+        for _ in 0..n {
+            let mut beams = Vec::new();
+            let mut next = Next::Beam(self.start);
+            while let Next::Beam(beam) = next {
+                beams.push(beam);
+                next = match self.step(beam) {
+                    // weight each side by its number of timelines so every timeline is
+                    // equally likely
+                    [Some(left), Some(right)] => {
+                        let left_paths = paths(left);
+                        let total = left_paths + paths(right);
+                        if rng.next_u128() % total < left_paths {
+                            left
+                        } else {
+                            right
+                        }
+                    }
+                    [Some(next), None] => next,
+                    _ => unreachable!("every beam has a successor"),
+                };
+            }
+            sampled.push(Timeline { beams, end: next });
+        }
+        Ok(sampled)
+    }
+
+    /// Draws the manifold with `beams` traced in: `|` and `-` for beams moving vertically and
+    /// horizontally through empty cells, `+` where they cross.
+    fn render<'a>(&self, beams: impl IntoIterator<Item = &'a Beam>) -> String {
+        let mut canvas: Vec<Vec<char>> = self
+            .cells
+            .iter()
+            .map(|row| row.iter().map(|&cell| cell.into()).collect())
+            .collect();
+        for beam in beams {
+            if self.cells[beam.row][beam.col] != Cell::Empty {
+                continue;
+            }
+            let glyph = &mut canvas[beam.row][beam.col];
+            *glyph = match (*glyph, beam.dir.is_vertical()) {
+                ('.' | '|', true) => '|',
+                ('.' | '-', false) => '-',
+                _ => '+',
+            };
+        }
+        canvas
+            .iter()
+            .map(|row| row.iter().collect::<String>() + "\n")
+            .collect()
+    }
+}
+
+/// Per-state timeline counts from [`BeamField::timeline_table`].
+#[derive(Debug)]
+struct TimelineTable {
+    /// remaining[state] -> number of timelines continuing from a beam state, 0 if unreachable
+    remaining: Vec<u128>,
+    /// Reachable beam states, each before every state it leads to
+    order: Vec<Beam>,
+}
+
+/// A single path of the beam from `S` to where it ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Timeline {
+    /// Beam state in every cell the path passes through
+    beams: Vec<Beam>,
+    /// How the path ends, never [`Next::Beam`]
+    end: Next,
+}
+
+impl Timeline {
+    /// Splitters along the path with the side the beam left each one by.
+    fn splits(&self, field: &BeamField) -> Vec<(usize, usize, Direction)> {
+        self.beams
+            .windows(2)
+            .filter(|pair| {
+                field.cells[pair[0].row][pair[0].col] == Cell::Splitter
+                    && pair[0].dir.is_vertical()
+            })
+            .map(|pair| {
+                let side = if pair[1].col < pair[0].col {
+                    Direction::Left
+                } else {
+                    Direction::Right
+                };
+                (pair[0].row, pair[0].col, side)
+            })
+            .collect()
+    }
+}
+
+/// Lazy depth-first enumeration of timelines, see [`BeamField::timelines`].
+struct Timelines<'a> {
+    field: &'a BeamField,
+    /// Beams of the path currently being extended
+    path: Vec<Beam>,
+    /// Branches still to explore: path length at the split and the beam taking the other side
+    pending: Vec<(usize, Next)>,
+}
+
+impl Iterator for Timelines<'_> {
+    type Item = Timeline;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (len, mut next) = self.pending.pop()?;
+        self.path.truncate(len);
+        while let Next::Beam(beam) = next {
+            self.path.push(beam);
+            let [first, second] = self.field.step(beam);
+            if let Some(second) = second {
+                self.pending.push((self.path.len(), second));
+            }
+            next = first.expect("every beam has a successor");
         }
+        Some(Timeline { beams: self.path.clone(), end: next })
+    }
+}
 
-        // update active indices
-        curr_beam_indices.extend(new_active);
+/// Small xorshift generator so sampling is reproducible from a seed.
+///
+/// See: <https://en.wikipedia.org/wiki/Xorshift>
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        // the state must never be zero
+        Self(seed ^ 0x9e37_79b9_7f4a_7c15 | 1)
+    }
 
-        #[cfg(test)]
-        println!("beams: {:?}", curr_beam_indices)
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
     }
 
-    let total_combinations = beam_counts.iter().sum();
+    fn next_u128(&mut self) -> u128 {
+        ((self.next_u64() as u128) << 64) | self.next_u64() as u128
+    }
+}
+
+/// Text heatmap of per-cell timeline counts, darker glyphs for more timelines on a log scale.
+fn render_heatmap(cell_timelines: &[Vec<u128>]) -> String {
+    const SHADES: [char; 10] =
+        [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+    let max = cell_timelines.iter().flatten().copied().max().unwrap_or(0);
+    let log = |count: u128| (u128::BITS - count.leading_zeros()) as usize;
+    cell_timelines
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&count| {
+                    if count == 0 {
+                        SHADES[0]
+                    } else {
+                        // 1..=SHADES.len() - 1 by bit length relative to the busiest cell
+                        let shade = 1
+                            + (log(count) - 1) * (SHADES.len() - 2)
+                                / log(max).max(1);
+                        SHADES[shade.min(SHADES.len() - 1)]
+                    }
+                })
+                .collect::<String>()
+                + "\n"
+        })
+        .collect()
+}
 
-    Ok((total_splits, total_combinations))
+fn compute_total_beam_splits(contents: &str) -> anyhow::Result<(usize, u128)> {
+    let field: BeamField = contents.parse()?;
+    Ok((field.count_splits(), field.count_timelines()?))
 }
 
 fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
     let contents = std::fs::read_to_string("./data/day-07-input.txt")?;
 
     /* Part 1 */
@@ -81,6 +627,43 @@ fn main() -> anyhow::Result<()> {
     /* Part 2 */
     println!("Part 2: {}", total_beam_combos);
 
+    let field: BeamField = contents.parse()?;
+    let timelines = match (args.paths, args.sample) {
+        (Some(n), _) => field.timelines()?.take(n).collect(),
+        (_, Some(n)) => {
+            field.sample_timelines(n, &mut XorShift::new(args.seed))?
+        }
+        (None, None) => Vec::new(),
+    };
+    for (idx, timeline) in timelines.iter().enumerate() {
+        let splits: Vec<String> = timeline
+            .splits(&field)
+            .iter()
+            .map(|(row, col, side)| {
+                format!("{}:{} {:?}", row + 1, col + 1, side)
+            })
+            .collect();
+        println!(
+            "Timeline {}: {:?} via [{}]",
+            idx + 1,
+            timeline.end,
+            splits.join(", ")
+        );
+    }
+    if args.render {
+        if timelines.is_empty() {
+            // no timelines picked, so trace every beam instead, without going through the
+            // exponentially many timelines
+            print!("{}", field.render(&field.reachable_beams()));
+        } else {
+            let beams = timelines.iter().flat_map(|timeline| &timeline.beams);
+            print!("{}", field.render(beams));
+        }
+    }
+    if args.heatmap {
+        print!("{}", render_heatmap(&field.cell_timelines()?));
+    }
+
     Ok(())
 }
 
@@ -133,4 +716,119 @@ mod test {
             ";
         assert_eq!(compute_total_beam_splits(input).unwrap().1, 40);
     }
+
+    #[test]
+    fn test_splitter_at_edge() {
+        // splitting in column 0 sends one beam out of the grid instead of panicking
+        let input = "
+S.
+..
+^.
+..";
+        assert_eq!(compute_total_beam_splits(input).unwrap(), (1, 2));
+    }
+
+    #[test]
+    fn test_mirrors_and_absorbers() {
+        // left beam is absorbed straight away, right beam is turned left into the same absorber
+        let input = "
+.S.
+...
+.^.
+#./";
+        assert_eq!(compute_total_beam_splits(input).unwrap(), (1, 2));
+    }
+
+    #[test]
+    fn test_beam_cycle() {
+        // the left half of the split enters a loop of four mirrors
+        let field: BeamField = r"
+...S.
+./\..
+.\/^."
+            .parse()
+            .unwrap();
+        assert_eq!(field.count_splits(), 1);
+        let err = field.count_timelines().unwrap_err();
+        assert!(err.to_string().contains("loops forever"), "{}", err);
+    }
+
+    const EXAMPLE: &str = "
+.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............";
+
+    #[test]
+    fn test_timelines_enumerated() {
+        let field: BeamField = EXAMPLE.parse().unwrap();
+        let timelines: Vec<Timeline> = field.timelines().unwrap().collect();
+        assert_eq!(timelines.len(), 40);
+        for timeline in &timelines {
+            assert_eq!(timeline.beams[0], field.start);
+            assert_eq!(timeline.end, Next::Exit);
+            assert_eq!(timeline.beams.last().unwrap().row, field.rows() - 1);
+        }
+        // all distinct, and the first one always goes left
+        let distinct: std::collections::HashSet<Vec<(usize, usize)>> =
+            timelines
+                .iter()
+                .map(|t| t.beams.iter().map(|b| (b.row, b.col)).collect())
+                .collect();
+        assert_eq!(distinct.len(), 40);
+        assert!(
+            timelines[0]
+                .splits(&field)
+                .iter()
+                .all(|&(_, _, side)| side == Direction::Left)
+        );
+    }
+
+    #[test]
+    fn test_cell_timelines() {
+        let field: BeamField = EXAMPLE.parse().unwrap();
+        let cells = field.cell_timelines().unwrap();
+        assert_eq!(cells[0][7], 1);
+        assert_eq!(cells[3][6] + cells[3][8], 2);
+        assert_eq!(cells[field.rows() - 1].iter().sum::<u128>(), 40);
+        let heatmap = render_heatmap(&cells);
+        assert_eq!(heatmap.lines().count(), field.rows());
+        assert_eq!(heatmap.lines().next().unwrap().trim(), ".");
+    }
+
+    #[test]
+    fn test_sample_and_render() {
+        let field: BeamField = "
+.S.
+...
+.^.
+..."
+        .parse()
+        .unwrap();
+        let sampled =
+            field.sample_timelines(20, &mut XorShift::new(7)).unwrap();
+        assert!(sampled.iter().all(|t| t.splits(&field).len() == 1));
+        assert!(sampled.iter().any(|t| t.beams.last().unwrap().col == 0));
+        assert!(sampled.iter().any(|t| t.beams.last().unwrap().col == 2));
+
+        let all: Vec<Timeline> = field.timelines().unwrap().collect();
+        let beams = all.iter().flat_map(|timeline| &timeline.beams);
+        assert_eq!(field.render(beams), ".S.\n.|.\n|^|\n|.|\n");
+        assert_eq!(
+            field.render(&field.reachable_beams()),
+            ".S.\n.|.\n|^|\n|.|\n"
+        );
+    }
 }
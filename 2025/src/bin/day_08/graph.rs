@@ -0,0 +1,277 @@
+//! Minimum spanning forests built from a stream of edges in ascending weight order.
+//!
+//! See: <https://en.wikipedia.org/wiki/Kruskal%27s_algorithm>
+
+use std::collections::BTreeMap;
+
+/// Undirected edge between two node indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub source: usize,
+    pub target: usize,
+}
+
+#[derive(Debug)]
+/// Disjoint set union over the graph nodes.
+///
+/// Invariant: only roots represent components
+pub struct DisjointSetUnion {
+    /// parent[i] -> idx of parent of node i
+    parent: Vec<usize>,
+    /// size[i] -> size of the node i's component
+    size: Vec<usize>,
+    /// Number of disjoint components
+    components: usize,
+    /// size_counts[s] -> number of components of size s
+    size_counts: BTreeMap<usize, usize>,
+}
+
+impl DisjointSetUnion {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(), // initially disjoint union of all n nodes
+            size: vec![1; n],         // all nodes initially disjoint
+            components: n,
+            size_counts: if n > 0 {
+                BTreeMap::from([(1, n)])
+            } else {
+                BTreeMap::new()
+            },
+        }
+    }
+
+    /// Find the component that contains node `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] != x {
+            // PERF: flatten trees for amortized log finds
+            self.parent[x] = self.find(self.parent[x]);
+        }
+        self.parent[x]
+    }
+
+    /// Connect the components containing nodes `a` and `b`.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let mut root_a = self.find(a);
+        let mut root_b = self.find(b);
+        // check if already connected
+        if root_a == root_b {
+            return false;
+        }
+
+        // Invariant: root_a is root of larger tree
+        // NOTE: this only affects local variables, not the DSU arrays
+        if self.size[root_a] < self.size[root_b] {
+            std::mem::swap(&mut root_a, &mut root_b);
+        }
+
+        self.remove_size(self.size[root_a]);
+        self.remove_size(self.size[root_b]);
+
+        // PERF: attach smaller tree under the larger one
+        self.parent[root_b] = root_a;
+        self.size[root_a] += self.size[root_b];
+        self.components -= 1;
+        *self.size_counts.entry(self.size[root_a]).or_default() += 1;
+        true
+    }
+
+    /// Number of disjoint components.
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// Sizes of the `k` largest components in descending order, fewer if there aren't `k`.
+    pub fn largest(&self, k: usize) -> Vec<usize> {
+        self.size_counts
+            .iter()
+            .rev()
+            .flat_map(|(&size, &count)| std::iter::repeat_n(size, count))
+            .take(k)
+            .collect()
+    }
+
+    fn remove_size(&mut self, size: usize) {
+        let count = self.size_counts.get_mut(&size).expect("tracked size");
+        *count -= 1;
+        if *count == 0 {
+            self.size_counts.remove(&size);
+        }
+    }
+}
+
+/// State of the components right after an edge has been considered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnionEvent {
+    pub edge: Edge,
+    /// Number of edges considered so far, including this one
+    pub pairs_considered: usize,
+    /// Whether the edge joined two components, i.e. belongs to the spanning forest
+    pub merged: bool,
+    /// Number of components after the edge
+    pub components: usize,
+    /// Largest component sizes after the edge, descending
+    pub largest: Vec<usize>,
+}
+
+/// Kruskal's algorithm as a stream: a [`UnionEvent`] for every edge of `edges`, which should be
+/// in ascending weight order, while the minimum spanning forest is built up.
+pub struct UnionEvents<I> {
+    edges: I,
+    dsu: DisjointSetUnion,
+    /// Edges that merged two components, in the order they were added
+    forest: Vec<Edge>,
+    /// Number of largest component sizes to report
+    top: usize,
+    pairs_considered: usize,
+}
+
+impl<I: Iterator<Item = Edge>> UnionEvents<I> {
+    /// Events for joining `n` initially disjoint nodes with `edges`, reporting the `top` largest
+    /// component sizes each time.
+    pub fn new(n: usize, edges: I, top: usize) -> Self {
+        Self {
+            edges,
+            dsu: DisjointSetUnion::new(n),
+            forest: Vec::with_capacity(n.saturating_sub(1)),
+            top,
+            pairs_considered: 0,
+        }
+    }
+
+    /// Edges of the whole minimum spanning forest, in the order they were added: runs the rest of
+    /// the events until the nodes form a single tree or the edges run out.
+    pub fn into_spanning_forest(mut self) -> Vec<Edge> {
+        while self.dsu.components() > 1 && self.next().is_some() {}
+        self.forest
+    }
+}
+
+impl<I: Iterator<Item = Edge>> Iterator for UnionEvents<I> {
+    type Item = UnionEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let edge = self.edges.next()?;
+        self.pairs_considered += 1;
+        let merged = self.dsu.union(edge.source, edge.target);
+        if merged {
+            self.forest.push(edge);
+        }
+        Some(UnionEvent {
+            edge,
+            pairs_considered: self.pairs_considered,
+            merged,
+            components: self.dsu.components(),
+            largest: self.dsu.largest(self.top),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn edges(pairs: &[(usize, usize)]) -> Vec<Edge> {
+        pairs.iter().map(|&(source, target)| Edge { source, target }).collect()
+    }
+
+    #[test]
+    fn test_dsu_tracks_components_and_sizes() {
+        let mut dsu = DisjointSetUnion::new(6);
+        assert_eq!(dsu.largest(10), vec![1; 6]);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(2, 3));
+        assert!(dsu.union(1, 3));
+        assert!(!dsu.union(0, 2));
+        assert_eq!(dsu.components(), 3);
+        assert_eq!(dsu.size_counts, BTreeMap::from([(1, 2), (4, 1)]));
+        assert_eq!(dsu.largest(2), vec![4, 1]);
+        assert_eq!(dsu.largest(5), vec![4, 1, 1]);
+    }
+
+    /// Fewest-weight forest with the most edges among every subset of `weighted`, by trying them
+    /// all: (edge count, total weight).
+    fn brute_force_forest(n: usize, weighted: &[(u64, Edge)]) -> (usize, u64) {
+        let mut best = (0, 0);
+        for subset in 0u32..1 << weighted.len() {
+            let mut dsu = DisjointSetUnion::new(n);
+            let mut weight = 0;
+            let acyclic = weighted
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| subset & (1 << idx) != 0)
+                .all(|(_, &(w, edge))| {
+                    weight += w;
+                    dsu.union(edge.source, edge.target)
+                });
+            let count = subset.count_ones() as usize;
+            if acyclic
+                && (count > best.0 || (count == best.0 && weight < best.1))
+            {
+                best = (count, weight);
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_minimum_spanning_forest_against_brute_force() {
+        let mut seed: u64 = 42;
+        let mut random = move || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            seed >> 33
+        };
+        // a complete graph on 6 nodes, and two separate triangles
+        let complete: Vec<(usize, usize)> =
+            (0..6).flat_map(|a| (a + 1..6).map(move |b| (a, b))).collect();
+        let triangles = [(0, 1), (1, 2), (0, 2), (3, 4), (4, 5), (3, 5)];
+        for (pairs, components) in [(&complete[..], 1), (&triangles[..], 2)] {
+            for _ in 0..5 {
+                let mut weighted: Vec<(u64, Edge)> = edges(pairs)
+                    .into_iter()
+                    .map(|edge| (random() % 100, edge))
+                    .collect();
+                weighted.sort_by_key(|&(w, _)| w);
+                let stream = weighted.iter().map(|&(_, edge)| edge);
+                let forest =
+                    UnionEvents::new(6, stream, 0).into_spanning_forest();
+                let weight = forest
+                    .iter()
+                    .map(|edge| {
+                        weighted.iter().find(|(_, e)| e == edge).unwrap().0
+                    })
+                    .sum();
+                assert_eq!(forest.len(), 6 - components);
+                assert_eq!(
+                    (forest.len(), weight),
+                    brute_force_forest(6, &weighted)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_union_events() {
+        let stream = edges(&[(0, 1), (1, 2), (0, 2), (3, 4), (2, 3)]);
+        let mut union_events =
+            UnionEvents::new(5, stream.clone().into_iter(), 2);
+        let events: Vec<UnionEvent> = union_events.by_ref().collect();
+        let merged: Vec<bool> = events.iter().map(|e| e.merged).collect();
+        assert_eq!(merged, vec![true, true, false, true, true]);
+        let components: Vec<usize> =
+            events.iter().map(|e| e.components).collect();
+        assert_eq!(components, vec![4, 3, 3, 2, 1]);
+        assert_eq!(events[3].largest, vec![3, 2]);
+        assert_eq!(events[4].pairs_considered, 5);
+
+        assert_eq!(
+            union_events.into_spanning_forest(),
+            edges(&[(0, 1), (1, 2), (3, 4), (2, 3)])
+        );
+        // the tree is complete after (2, 3), so the edge after it is never read
+        let mut stream = stream.into_iter().chain(edges(&[(1, 4)]));
+        let forest =
+            UnionEvents::new(5, stream.by_ref(), 2).into_spanning_forest();
+        assert_eq!(forest, edges(&[(0, 1), (1, 2), (3, 4), (2, 3)]));
+        assert_eq!(stream.next(), Some(Edge { source: 1, target: 4 }));
+    }
+}
@@ -0,0 +1,233 @@
+//! 3D k-d tree over integer points, used to generate close pairs lazily.
+//!
+//! See: <https://en.wikipedia.org/wiki/K-d_tree>
+
+use aoc_common::parallel;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// Integer point in 3D space.
+pub type Point = [i64; 3];
+
+/// Largest coordinate difference along an axis for which [`squared_distance`] is exact: three
+/// squares below 2^62 each still add up to less than 2^64.
+pub const MAX_COORDINATE: i64 = (1 << 31) - 1;
+
+/// Squared straight line distance, exact for points whose coordinates are all in
+/// `0..=MAX_COORDINATE`.
+pub fn squared_distance(a: &Point, b: &Point) -> u64 {
+    a.iter().zip(b).map(|(a, b)| a.abs_diff(*b).pow(2)).sum()
+}
+
+/// Static k-d tree stored implicitly: each subtree is a slice of `order` whose middle element
+/// splits it along axis `depth % 3`.
+#[derive(Debug)]
+pub struct KdTree {
+    points: Vec<Point>,
+    /// Point indices arranged so that `order[lo..hi]` is a subtree
+    order: Vec<usize>,
+}
+
+impl KdTree {
+    pub fn new(points: Vec<Point>) -> Self {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        Self::build(&points, &mut order, 0);
+        Self { points, order }
+    }
+
+    fn build(points: &[Point], order: &mut [usize], depth: usize) {
+        if order.len() <= 1 {
+            return;
+        }
+        let axis = depth % 3;
+        let mid = order.len() / 2;
+        order.select_nth_unstable_by_key(mid, |&idx| points[idx][axis]);
+        let (left, right) = order.split_at_mut(mid);
+        Self::build(points, left, depth + 1);
+        Self::build(points, &mut right[1..], depth + 1);
+    }
+
+    /// The `k` points closest to `points[query]` with an index above `query`, as ascending
+    /// `(squared distance, index)` pairs.
+    pub fn nearest_above(&self, query: usize, k: usize) -> Vec<(u64, usize)> {
+        // max-heap of the best candidates so far, so the worst is cheap to evict
+        let mut best: BinaryHeap<(u64, usize)> = BinaryHeap::with_capacity(k);
+        if k > 0 {
+            self.search(query, k, 0, self.order.len(), 0, &mut best);
+        }
+        best.into_sorted_vec()
+    }
+
+    fn search(
+        &self,
+        query: usize,
+        k: usize,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        best: &mut BinaryHeap<(u64, usize)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let idx = self.order[mid];
+        let target = &self.points[query];
+
+        if idx > query {
+            let candidate = (squared_distance(target, &self.points[idx]), idx);
+            if best.len() < k {
+                best.push(candidate);
+            } else if best.peek().is_some_and(|&worst| candidate < worst) {
+                best.pop();
+                best.push(candidate);
+            }
+        }
+
+        let axis = depth % 3;
+        let diff = target[axis] - self.points[idx][axis];
+        let (near, far) = if diff < 0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.search(query, k, near.0, near.1, depth + 1, best);
+        // the far side can only help if the splitting plane is closer than the worst candidate
+        let plane = diff.unsigned_abs().pow(2);
+        if best.len() < k
+            || best.peek().is_some_and(|&(worst, _)| plane <= worst)
+        {
+            self.search(query, k, far.0, far.1, depth + 1, best);
+        }
+    }
+}
+
+/// Per-point cursor over its neighbours with a higher index, closest first.
+#[derive(Debug)]
+struct Cursor {
+    /// Neighbours fetched so far, ascending
+    neighbours: Vec<(u64, usize)>,
+    /// Next entry of `neighbours` to hand out
+    next: usize,
+    /// Number of neighbours asked for in the last query
+    k: usize,
+}
+
+/// Every pair of points exactly once, in ascending order of squared distance (ties broken by
+/// index), without materialising all n² pairs.
+///
+/// A heap holds the next unused neighbour of every point. When a point's fetched neighbours run
+/// out, the k-nearest query is repeated with twice the `k`.
+pub struct ClosestPairs {
+    tree: KdTree,
+    cursors: Vec<Cursor>,
+    /// (squared distance, point, neighbour) of each point's next pair
+    heap: BinaryHeap<Reverse<(u64, usize, usize)>>,
+}
+
+/// Neighbours fetched for a point the first time it is queried.
+const INITIAL_NEIGHBOURS: usize = 8;
+
+impl ClosestPairs {
+    pub fn new(points: Vec<Point>) -> Self {
+        let n = points.len();
+        let tree = KdTree::new(points);
+        // the first query of every point is independent of the others
+        let queries: Vec<usize> = (0..n).collect();
+        let first = parallel::map(&queries, |&i| {
+            tree.nearest_above(i, INITIAL_NEIGHBOURS)
+        });
+        let mut cursors = Vec::with_capacity(n);
+        let mut heap = BinaryHeap::with_capacity(n);
+        for (i, neighbours) in first.into_iter().enumerate() {
+            if let Some(&(dist2, j)) = neighbours.first() {
+                heap.push(Reverse((dist2, i, j)));
+            }
+            cursors.push(Cursor {
+                neighbours,
+                next: 1,
+                k: INITIAL_NEIGHBOURS,
+            });
+        }
+        Self { tree, cursors, heap }
+    }
+
+    /// The next unused neighbour of point `i`, querying further out when needed.
+    fn advance(&mut self, i: usize) -> Option<(u64, usize)> {
+        let cursor = &mut self.cursors[i];
+        if cursor.next >= cursor.neighbours.len() {
+            if cursor.neighbours.len() < cursor.k {
+                return None; // a short answer means there are no more
+            }
+            cursor.k *= 2;
+            cursor.neighbours = self.tree.nearest_above(i, cursor.k);
+            if cursor.next >= cursor.neighbours.len() {
+                return None;
+            }
+        }
+        let neighbour = cursor.neighbours[cursor.next];
+        cursor.next += 1;
+        Some(neighbour)
+    }
+}
+
+impl Iterator for ClosestPairs {
+    /// (squared distance, lower index, higher index)
+    type Item = (u64, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((dist2, i, j)) = self.heap.pop()?;
+        if let Some((next_dist2, next_j)) = self.advance(i) {
+            self.heap.push(Reverse((next_dist2, i, next_j)));
+        }
+        Some((dist2, i, j))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Deterministic pseudo-random points, with plenty of duplicate coordinates.
+    fn points(n: usize) -> Vec<Point> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 50) as i64
+        };
+        (0..n).map(|_| [next(), next(), next()]).collect()
+    }
+
+    #[test]
+    fn test_nearest_above_matches_brute_force() {
+        let points = points(200);
+        let tree = KdTree::new(points.clone());
+        for query in [0, 17, 150, 199] {
+            let mut expected: Vec<(u64, usize)> = (query + 1..points.len())
+                .map(|j| (squared_distance(&points[query], &points[j]), j))
+                .collect();
+            expected.sort();
+            expected.truncate(10);
+            assert_eq!(tree.nearest_above(query, 10), expected);
+        }
+    }
+
+    #[test]
+    fn test_closest_pairs_matches_brute_force() {
+        let points = points(120);
+        let mut expected = Vec::new();
+        for i in 0..points.len() {
+            for j in (i + 1)..points.len() {
+                expected.push((
+                    squared_distance(&points[i], &points[j]),
+                    i,
+                    j,
+                ));
+            }
+        }
+        expected.sort();
+        let pairs: Vec<_> = ClosestPairs::new(points).collect();
+        assert_eq!(pairs, expected);
+    }
+}
@@ -0,0 +1,372 @@
+use aoc_common::parallel;
+use clap::Parser;
+use graph::{Edge, UnionEvents};
+use kdtree::{ClosestPairs, MAX_COORDINATE, Point};
+use std::str::FromStr;
+
+mod graph;
+mod kdtree;
+
+/// Position with XYZ coordinates.
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    /// X position
+    x: usize,
+    /// Y position
+    y: usize,
+    /// Z position
+    z: usize,
+}
+
+impl Position {
+    fn new(x: usize, y: usize, z: usize) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Coordinates must be at most [`MAX_COORDINATE`], as checked when parsing.
+    fn to_point(self) -> Point {
+        let coord =
+            |c: usize| i64::try_from(c).expect("coordinate checked on parse");
+        [coord(self.x), coord(self.y), coord(self.z)]
+    }
+}
+
+/// Pairs of junctions in ascending order of straight line distance, generated lazily from a k-d
+/// tree so only the pairs actually consumed are ever built. Distances are compared squared, which
+/// is exact for integer coordinates.
+///
+/// See: https://en.wikipedia.org/wiki/Euclidean_distance
+fn closest_edges(junctions: &[Junction]) -> impl Iterator<Item = Edge> {
+    let points = junctions.iter().map(|j| j.position.to_point()).collect();
+    ClosestPairs::new(points)
+        .map(|(_, source, target)| Edge { source, target })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Junction {
+    position: Position,
+}
+
+impl Junction {
+    fn new(position: Position) -> Self {
+        Self { position }
+    }
+}
+
+impl FromStr for Junction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut iter = s.split(',').map(|coord| {
+            let coord = coord.parse::<usize>()?;
+            if coord as u64 > MAX_COORDINATE as u64 {
+                anyhow::bail!(
+                    "coordinate {} is above {}, too large for exact distances",
+                    coord,
+                    MAX_COORDINATE
+                );
+            }
+            Ok(coord)
+        });
+        let x = iter.next().ok_or_else(|| anyhow::anyhow!("missing x"))??;
+        let y = iter.next().ok_or_else(|| anyhow::anyhow!("missing y"))??;
+        let z = iter.next().ok_or_else(|| anyhow::anyhow!("missing z"))??;
+        if iter.next().is_some() {
+            anyhow::bail!("too many coords");
+        }
+        let position = Position::new(x, y, z);
+        Ok(Junction::new(position))
+    }
+}
+
+fn parse_junctions(contents: &str) -> anyhow::Result<Vec<Junction>> {
+    contents.trim().lines().map(str::parse).collect()
+}
+
+/// How the part 1 connection budget is spent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConnectionPolicy {
+    /// Stop after the first N closest pairs, counting pairs that were already in the same
+    /// circuit. This is what the puzzle asks for.
+    PairsConsidered(usize),
+    /// Stop after the first N pairs that actually joined two circuits, skipping redundant ones.
+    Merges(usize),
+}
+
+impl ConnectionPolicy {
+    fn budget(self) -> usize {
+        match self {
+            Self::PairsConsidered(n) | Self::Merges(n) => n,
+        }
+    }
+
+    /// Whether the budget is used up after `pairs_considered` pairs of which `merges` joined two
+    /// circuits.
+    fn is_spent(self, pairs_considered: usize, merges: usize) -> bool {
+        match self {
+            Self::PairsConsidered(n) => pairs_considered >= n,
+            Self::Merges(n) => merges >= n,
+        }
+    }
+}
+
+/// Product of the three largest circuit sizes, or an error if there are fewer than three.
+fn top3_product(
+    largest: &[usize],
+    components: usize,
+) -> anyhow::Result<usize> {
+    if largest.len() < 3 {
+        anyhow::bail!("need at least 3 circuits, found {}", components);
+    }
+    Ok(largest[..3].iter().product())
+}
+
+/// What a single pass of Kruskal's algorithm over the closest pairs finds.
+struct Answers {
+    part1: anyhow::Result<usize>,
+    part2: anyhow::Result<usize>,
+    /// Cables of the minimum spanning forest, shortest first; a tree when part 2 has an answer.
+    tree: Vec<Edge>,
+}
+
+/// Answers both parts from a single pass of Kruskal's algorithm over the closest pairs.
+///
+/// Part 1: connect the closest pairs of junction boxes as allowed by `policy` and return the
+/// product of the three largest circuit sizes. If the pairs run out first, the final circuits
+/// are used.
+///
+/// Part 2: connect all the closest unconnected pairs of junction boxes together until they're all
+/// in one circuit, then return the product of the X coordinates of the last two junction boxes
+/// needed to connect.
+///
+/// Each part fails on its own, so a part 1 policy that leaves too few circuits still gives the
+/// part 2 answer.
+fn solve(junctions: &[Junction], policy: ConnectionPolicy) -> Answers {
+    let n = junctions.len();
+    let mut events = UnionEvents::new(n, closest_edges(junctions), 3);
+    // circuits before any connection: every junction on its own
+    let mut largest = vec![1; n.min(3)];
+    let mut components = n;
+    let mut merges = 0;
+    let mut part1 = None;
+    let mut part2 = None;
+    if policy.budget() == 0 {
+        part1 = Some(top3_product(&largest, components));
+    }
+    for event in events.by_ref() {
+        merges += usize::from(event.merged);
+        largest = event.largest;
+        components = event.components;
+        if part1.is_none() && policy.is_spent(event.pairs_considered, merges) {
+            part1 = Some(top3_product(&largest, components));
+        }
+        if part2.is_none() && event.merged && event.components == 1 {
+            let a = junctions[event.edge.source].position.x;
+            let b = junctions[event.edge.target].position.x;
+            part2 = Some(a * b);
+        }
+        if part1.is_some() && part2.is_some() {
+            break;
+        }
+    }
+
+    // the pass only stops early once part 2 has joined everything, so the forest is complete
+    let tree = events.into_spanning_forest();
+    let part1 = part1.unwrap_or_else(|| top3_product(&largest, components));
+    let part2 = part2.ok_or_else(|| {
+        anyhow::anyhow!(
+            "junctions never form a single circuit ({} spanning edges for {} junctions)",
+            tree.len(),
+            n
+        )
+    });
+    Answers { part1, part2, tree }
+}
+
+#[derive(Parser)]
+struct Args {
+    /// Number of connections to make for part 1.
+    #[arg(long, default_value_t = 1000)]
+    connections: usize,
+    /// Count only connections that join two circuits, skipping pairs already connected.
+    #[arg(long)]
+    count_merges: bool,
+    /// List the cables of the minimum spanning tree, shortest first.
+    #[arg(long)]
+    tree: bool,
+    /// Worker threads for the nearest neighbour queries, 0 for one per CPU. More than one needs
+    /// the `parallel` feature.
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    parallel::set_threads(args.threads).map_err(anyhow::Error::msg)?;
+    let contents = std::fs::read_to_string("./data/day-08-input.txt")?;
+
+    let policy = if args.count_merges {
+        ConnectionPolicy::Merges(args.connections)
+    } else {
+        ConnectionPolicy::PairsConsidered(args.connections)
+    };
+    let junctions = parse_junctions(&contents)?;
+    let Answers { part1, part2, tree } = solve(&junctions, policy);
+
+    /* Part 1 */
+    match &part1 {
+        Ok(part1) => println!("Part 1: {}", part1),
+        Err(e) => eprintln!("Part 1: {:#}", e),
+    }
+
+    /* Part 2 */
+    match &part2 {
+        Ok(part2) => println!("Part 2: {}", part2),
+        Err(e) => eprintln!("Part 2: {:#}", e),
+    }
+
+    if args.tree {
+        println!("Minimum spanning tree: {} cables", tree.len());
+        for edge in tree {
+            let a = junctions[edge.source].position;
+            let b = junctions[edge.target].position;
+            println!("{},{},{} - {},{},{}", a.x, a.y, a.z, b.x, b.y, b.z);
+        }
+    }
+
+    if part1.is_err() || part2.is_err() {
+        anyhow::bail!("not every part could be answered");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_part1_example() {
+        let input = "
+162,817,812
+57,618,57
+906,360,560
+592,479,940
+352,342,300
+466,668,158
+542,29,236
+431,825,988
+739,650,466
+52,470,668
+216,146,977
+819,987,18
+117,168,530
+805,96,715
+346,949,466
+970,615,88
+941,993,340
+862,61,35
+984,92,344
+425,690,689";
+        let junctions = parse_junctions(input).unwrap();
+        assert_eq!(
+            solve(&junctions, ConnectionPolicy::PairsConsidered(10))
+                .part1
+                .unwrap(),
+            40
+        )
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let input = "
+162,817,812
+57,618,57
+906,360,560
+592,479,940
+352,342,300
+466,668,158
+542,29,236
+431,825,988
+739,650,466
+52,470,668
+216,146,977
+819,987,18
+117,168,530
+805,96,715
+346,949,466
+970,615,88
+941,993,340
+862,61,35
+984,92,344
+425,690,689";
+        let junctions = parse_junctions(input).unwrap();
+        let answers = solve(&junctions, ConnectionPolicy::PairsConsidered(10));
+        assert_eq!(answers.part2.unwrap(), 25272);
+        // the last cable of the tree is the one part 2 is about
+        assert_eq!(answers.tree.len(), 19);
+        let last = answers.tree.last().unwrap();
+        assert_eq!(
+            junctions[last.source].position.x
+                * junctions[last.target].position.x,
+            25272
+        );
+    }
+
+    /// Junctions along a line, so the closest pairs are easy to follow: gaps of 1, 2, 3 and 10
+    /// between neighbours, then a far away pair.
+    const LINE: &str =
+        "0,0,0\n1,0,0\n3,0,0\n6,0,0\n16,0,0\n1000,0,0\n1001,0,0";
+
+    #[test]
+    fn test_policy_redundant_pairs() {
+        let junctions = parse_junctions(LINE).unwrap();
+        // closest pairs: 0-1 (1), 5-6 (1), 1-2 (2), 0-2 (3, redundant), 2-3 (3), ...
+        let part1 =
+            solve(&junctions, ConnectionPolicy::PairsConsidered(4)).part1;
+        assert_eq!(part1.unwrap(), 6); // {0,1,2}, {5,6}, {3}
+        let part1 = solve(&junctions, ConnectionPolicy::Merges(4)).part1;
+        assert_eq!(part1.unwrap(), 8); // {0,1,2,3}, {5,6}, {4}
+        let part1 =
+            solve(&junctions, ConnectionPolicy::PairsConsidered(0)).part1;
+        assert_eq!(part1.unwrap(), 1);
+    }
+
+    #[test]
+    fn test_policy_fewer_than_three_circuits() {
+        let junctions = parse_junctions(LINE).unwrap();
+        let Answers { part1, part2, .. } =
+            solve(&junctions, ConnectionPolicy::Merges(5));
+        assert_eq!(
+            part1.unwrap_err().to_string(),
+            "need at least 3 circuits, found 2"
+        );
+        // part 2 still comes out, from the last link between 16 and 1000
+        assert_eq!(part2.unwrap(), 16000);
+        let Answers { part1, part2, .. } =
+            solve(&junctions, ConnectionPolicy::PairsConsidered(100));
+        assert_eq!(
+            part1.unwrap_err().to_string(),
+            "need at least 3 circuits, found 1"
+        );
+        assert_eq!(part2.unwrap(), 16000);
+    }
+
+    #[test]
+    fn test_coordinates_fit_exact_distances() {
+        let far: Junction = format!("0,0,{}", MAX_COORDINATE).parse().unwrap();
+        let origin: Junction = "0,0,0".parse().unwrap();
+        let d = kdtree::squared_distance(
+            &far.position.to_point(),
+            &origin.position.to_point(),
+        );
+        assert_eq!(d, (MAX_COORDINATE as u64).pow(2));
+
+        let err = format!("0,{},0", MAX_COORDINATE + 1)
+            .parse::<Junction>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "coordinate 2147483648 is above 2147483647, too large for exact distances"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use clap::Parser;
+use polygon::{Polygon, Tile, area, best_rectangle, from_xy};
+use svg::Highlight;
+
+mod polygon;
+mod svg;
+
+#[derive(Parser)]
+struct Args {
+    /// Write an SVG drawing of the tiles and both parts' best rectangles to this path.
+    #[arg(long)]
+    render: Option<std::path::PathBuf>,
+}
+
+fn parse_coords(contents: &str) -> anyhow::Result<Vec<Tile>> {
+    contents
+        .trim()
+        .lines()
+        .enumerate()
+        .map(|(idx, l)| {
+            Ok(if let Some((x, y)) = l.split_once(",") {
+                from_xy(x.parse::<usize>()?, y.parse::<usize>()?)
+            } else {
+                anyhow::bail!("Line {} is invalid: {}", idx + 1, l)
+            })
+        })
+        .collect()
+}
+
+/// Maximum area found by constructing a rectangle with at least two corners that are red tiles.
+fn max_area(red_coords: &[Tile]) -> usize {
+    best_rectangle(red_coords, |_, _| true).map_or(0, |(a, b)| area(a, b))
+}
+
+/// Maximum area found by constructing a rectangle with at least two corners that are red tiles,
+/// covering only red and green tiles.
+fn max_area_red_green(red_coords: &[Tile]) -> anyhow::Result<usize> {
+    let polygon = Polygon::new(red_coords.to_vec())?;
+    Ok(polygon.largest_rectangle().map_or(0, |(a, b)| area(a, b)))
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let contents = std::fs::read_to_string("./data/day-09-input.txt")?;
+    let contents = contents.trim();
+    let coords = parse_coords(contents)?;
+
+    /* Part 1 */
+    println!("Part 1: {}", max_area(&coords));
+
+    /* Part 2 */
+    println!("Part 2: {}", max_area_red_green(&coords)?);
+
+    if let Some(path) = args.render {
+        let polygon = Polygon::new(coords.clone())?;
+        let mut highlights = Vec::new();
+        if let Some(corners) = best_rectangle(&coords, |_, _| true) {
+            highlights.push(Highlight {
+                corners,
+                label: "Part 1",
+                colour: "blue",
+            });
+        }
+        if let Some(corners) = polygon.largest_rectangle() {
+            highlights.push(Highlight {
+                corners,
+                label: "Part 2",
+                colour: "purple",
+            });
+        }
+        std::fs::write(path, svg::render(&polygon, &highlights))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_part1_example() {
+        let input = "
+7,1
+11,1
+11,7
+9,7
+9,5
+2,5
+2,3
+7,3            ";
+        let coords = parse_coords(input).unwrap();
+        assert_eq!(max_area(&coords), 50)
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let input = "
+7,1
+11,1
+11,7
+9,7
+9,5
+2,5
+2,3
+7,3            ";
+        let coords = parse_coords(input).unwrap();
+        assert_eq!(max_area_red_green(&coords).unwrap(), 24)
+    }
+}
@@ -0,0 +1,465 @@
+//! Orthogonal (rectilinear) polygons over integer tiles.
+//!
+//! The polygon is given by the tiles at its corners, joined in order by straight horizontal or
+//! vertical runs of tiles. A tile is covered if it lies on the boundary or strictly inside it.
+//!
+//! See: <https://en.wikipedia.org/wiki/Rectilinear_polygon>
+
+use std::{cmp::Ordering, ops::Range};
+
+/// (row, col) of a tile.
+pub type Tile = (usize, usize);
+
+/// Tile at puzzle coordinates `x,y`, with `x` growing to the right and `y` growing downwards as
+/// in the example diagrams.
+///
+/// NOTE: the puzzle lists x (the column) first, so this and [`to_xy`] are the only places the
+/// axes get swapped.
+pub fn from_xy(x: usize, y: usize) -> Tile {
+    (y, x)
+}
+
+/// Puzzle coordinates `(x, y)` of a tile, see [`from_xy`].
+pub fn to_xy((row, col): Tile) -> (usize, usize) {
+    (col, row)
+}
+
+/// Number of tiles in the rectangle with opposite corners `a` and `b`.
+pub fn area(a: Tile, b: Tile) -> usize {
+    (a.0.abs_diff(b.0) + 1) * (a.1.abs_diff(b.1) + 1)
+}
+
+/// Opposite corners of the largest rectangle spanned by two of `corners` that `accept` allows.
+pub fn best_rectangle(
+    corners: &[Tile],
+    accept: impl Fn(Tile, Tile) -> bool,
+) -> Option<(Tile, Tile)> {
+    let mut best: Option<(Tile, Tile)> = None;
+    for (i, &a) in corners.iter().enumerate() {
+        for &b in &corners[i..] {
+            let larger = best.is_none_or(|(c, d)| area(a, b) > area(c, d));
+            if larger && accept(a, b) {
+                best = Some((a, b));
+            }
+        }
+    }
+    best
+}
+
+/// Straight run of boundary tiles between two consecutive corners.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    from: Tile,
+    to: Tile,
+}
+
+impl Edge {
+    fn is_horizontal(&self) -> bool {
+        self.from.0 == self.to.0
+    }
+
+    fn rows(&self) -> (usize, usize) {
+        (self.from.0.min(self.to.0), self.from.0.max(self.to.0))
+    }
+
+    fn cols(&self) -> (usize, usize) {
+        (self.from.1.min(self.to.1), self.from.1.max(self.to.1))
+    }
+
+    /// Step direction along the edge, as the sign of each coordinate change.
+    fn direction(&self) -> (Ordering, Ordering) {
+        (self.to.0.cmp(&self.from.0), self.to.1.cmp(&self.from.1))
+    }
+
+    /// Whether the two edges share at least one tile.
+    fn touches(&self, other: &Edge) -> bool {
+        let (a_rows, a_cols) = (self.rows(), self.cols());
+        let (b_rows, b_cols) = (other.rows(), other.cols());
+        a_rows.0.max(b_rows.0) <= a_rows.1.min(b_rows.1)
+            && a_cols.0.max(b_cols.0) <= a_cols.1.min(b_cols.1)
+    }
+}
+
+/// One axis of the compressed grid: every corner coordinate gets a slot of its own, and each run
+/// of coordinates strictly between two consecutive corner coordinates shares one gap slot.
+///
+/// Slots are weighted by how many coordinates they stand for, so a one tile wide notch between
+/// two corners is still a slot of its own rather than disappearing.
+#[derive(Debug)]
+struct Axis {
+    /// First coordinate of each slot, ascending
+    starts: Vec<usize>,
+    /// prefix[i] -> number of coordinates in slots `..i`
+    prefix: Vec<usize>,
+}
+
+impl Axis {
+    fn new(mut coords: Vec<usize>) -> Self {
+        coords.sort_unstable();
+        coords.dedup();
+        let mut starts = Vec::with_capacity(2 * coords.len());
+        for (i, &coord) in coords.iter().enumerate() {
+            starts.push(coord);
+            if coords.get(i + 1).is_some_and(|&next| next > coord + 1) {
+                starts.push(coord + 1);
+            }
+        }
+        let mut prefix = vec![0];
+        for (i, &start) in starts.iter().enumerate() {
+            // the last slot is a corner coordinate, so it has weight 1
+            let end = starts.get(i + 1).copied().unwrap_or(start + 1);
+            prefix.push(prefix[i] + end - start);
+        }
+        Self { starts, prefix }
+    }
+
+    fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Slot of a corner coordinate.
+    fn slot(&self, coord: usize) -> usize {
+        self.starts.binary_search(&coord).expect("corner coordinate")
+    }
+
+    /// Number of coordinates covered by slots `range`.
+    fn weight(&self, range: Range<usize>) -> usize {
+        self.prefix[range.end] - self.prefix[range.start]
+    }
+}
+
+/// Rectilinear polygon with a 2D prefix sum of covered area over the coordinate-compressed
+/// grid, so rectangles can be checked without walking their tiles.
+///
+/// Corners may be listed clockwise or counter-clockwise.
+#[derive(Debug)]
+pub struct Polygon {
+    corners: Vec<Tile>,
+    rows: Axis,
+    cols: Axis,
+    /// covered[r][c] -> number of covered tiles in row slots `..r` and column slots `..c`
+    covered: Vec<Vec<usize>>,
+}
+
+impl Polygon {
+    /// Builds the polygon joining `corners` in order and back to the first one.
+    ///
+    /// Fails if two consecutive corners are not in line, or if the boundary runs over itself.
+    pub fn new(corners: Vec<Tile>) -> anyhow::Result<Self> {
+        if corners.len() < 4 {
+            anyhow::bail!(
+                "polygon needs at least 4 corners, found {}",
+                corners.len()
+            );
+        }
+        let edges = Self::edges(&corners)?;
+        Self::check_simple(&edges)?;
+
+        let rows = Axis::new(corners.iter().map(|t| t.0).collect());
+        let cols = Axis::new(corners.iter().map(|t| t.1).collect());
+
+        let grid = Self::rasterise(&edges, &rows, &cols);
+        let mut covered = vec![vec![0; cols.len() + 1]; rows.len() + 1];
+        for r in 0..rows.len() {
+            for c in 0..cols.len() {
+                let area = rows.weight(r..r + 1) * cols.weight(c..c + 1);
+                covered[r + 1][c + 1] = if grid[r][c] { area } else { 0 }
+                    + covered[r][c + 1]
+                    + covered[r + 1][c]
+                    - covered[r][c];
+            }
+        }
+
+        Ok(Self { corners, rows, cols, covered })
+    }
+
+    fn edges(corners: &[Tile]) -> anyhow::Result<Vec<Edge>> {
+        let next = corners.iter().skip(1).chain(corners.first());
+        corners
+            .iter()
+            .zip(next)
+            .enumerate()
+            .map(|(idx, (&from, &to))| {
+                if from == to {
+                    anyhow::bail!("corner {} repeats {:?}", idx + 1, from);
+                }
+                if from.0 != to.0 && from.1 != to.1 {
+                    anyhow::bail!(
+                        "cannot connect points in straight line: {:?} {:?}",
+                        from,
+                        to
+                    );
+                }
+                Ok(Edge { from, to })
+            })
+            .collect()
+    }
+
+    /// Rejects boundaries that touch or cross themselves anywhere other than at the shared
+    /// corner of consecutive edges.
+    fn check_simple(edges: &[Edge]) -> anyhow::Result<()> {
+        let n = edges.len();
+        for i in 0..n {
+            let (a, b) = (&edges[i], &edges[(i + 1) % n]);
+            let (row_dir, col_dir) = a.direction();
+            if b.direction() == (row_dir.reverse(), col_dir.reverse()) {
+                anyhow::bail!(
+                    "self-intersecting polygon: boundary doubles back at {:?}",
+                    a.to
+                );
+            }
+            // consecutive edges meet at a corner, skip them (and the wrap around pair)
+            for (j, other) in edges.iter().enumerate().skip(i + 2) {
+                if i == 0 && j == n - 1 {
+                    continue;
+                }
+                if a.touches(other) {
+                    anyhow::bail!(
+                        "self-intersecting polygon: edge {:?}-{:?} meets edge {:?}-{:?}",
+                        a.from,
+                        a.to,
+                        other.from,
+                        other.to
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Covered compressed slots: boundary slots, then everything inside by crossing parity.
+    ///
+    /// Every tile of a slot is covered or not alike, since no corner lies strictly inside a slot.
+    fn rasterise(edges: &[Edge], rows: &Axis, cols: &Axis) -> Vec<Vec<bool>> {
+        let mut grid = vec![vec![false; cols.len()]; rows.len()];
+        for edge in edges {
+            let (r0, r1) = edge.rows();
+            let (c0, c1) = edge.cols();
+            for row in &mut grid[rows.slot(r0)..=rows.slot(r1)] {
+                row[cols.slot(c0)..=cols.slot(c1)].fill(true);
+            }
+        }
+
+        // A tile off the boundary is inside if an odd number of vertical edges lie to its
+        // left. Counting edges over the half-open span `top..bottom` makes a corner shared by
+        // two edges count once (or twice, for a turn back), which is the usual crossing rule.
+        let verticals: Vec<&Edge> =
+            edges.iter().filter(|e| !e.is_horizontal()).collect();
+        for (r, &row) in rows.starts.iter().enumerate() {
+            let mut crossings: Vec<usize> = verticals
+                .iter()
+                .filter(|e| {
+                    let (top, bottom) = e.rows();
+                    top <= row && row < bottom
+                })
+                .map(|e| cols.slot(e.from.1))
+                .collect();
+            crossings.sort_unstable();
+            let mut crossings = crossings.into_iter().peekable();
+            let mut inside = false;
+            for (c, tile) in grid[r].iter_mut().enumerate() {
+                while crossings.next_if(|&x| x < c).is_some() {
+                    inside = !inside;
+                }
+                *tile |= inside;
+            }
+        }
+        grid
+    }
+
+    /// Whether every tile of the rectangle with opposite corners `a` and `b` is covered.
+    ///
+    /// Both must be corners of the polygon. O(log n) for the coordinate lookups, O(1) after.
+    pub fn contains_rect(&self, a: Tile, b: Tile) -> bool {
+        let r0 = self.rows.slot(a.0.min(b.0));
+        let r1 = self.rows.slot(a.0.max(b.0)) + 1;
+        let c0 = self.cols.slot(a.1.min(b.1));
+        let c1 = self.cols.slot(a.1.max(b.1)) + 1;
+        let covered = self.covered[r1][c1] + self.covered[r0][c0]
+            - self.covered[r0][c1]
+            - self.covered[r1][c0];
+        covered == self.rows.weight(r0..r1) * self.cols.weight(c0..c1)
+    }
+
+    /// Corners in input order.
+    pub fn corners(&self) -> &[Tile] {
+        &self.corners
+    }
+
+    /// Opposite corners of the largest covered rectangle with two corners of the polygon as
+    /// opposite corners.
+    pub fn largest_rectangle(&self) -> Option<(Tile, Tile)> {
+        best_rectangle(&self.corners, |a, b| self.contains_rect(a, b))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The puzzle example, as (row, col).
+    const EXAMPLE: [Tile; 8] =
+        [(1, 7), (1, 11), (7, 11), (7, 9), (5, 9), (5, 2), (3, 2), (3, 7)];
+
+    #[test]
+    fn test_both_orientations() {
+        let clockwise = Polygon::new(EXAMPLE.to_vec()).unwrap();
+        let mut reversed = EXAMPLE.to_vec();
+        reversed.reverse();
+        let counter_clockwise = Polygon::new(reversed).unwrap();
+        for polygon in [clockwise, counter_clockwise] {
+            assert!(polygon.contains_rect((3, 2), (5, 9)));
+            assert!(polygon.contains_rect((1, 7), (5, 11)));
+            assert!(!polygon.contains_rect((3, 2), (7, 9)));
+            assert!(!polygon.contains_rect((1, 7), (3, 2)));
+            let best = polygon.largest_rectangle().map(|(a, b)| area(a, b));
+            assert_eq!(best, Some(24));
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_boundaries() {
+        let diagonal = vec![(0, 0), (0, 5), (5, 5), (6, 0)];
+        assert!(Polygon::new(diagonal).is_err());
+
+        // figure of eight: the last edge runs across the first
+        let crossing = vec![(0, 2), (4, 2), (4, 6), (2, 6), (2, 0), (0, 0)];
+        let err = Polygon::new(crossing).unwrap_err();
+        assert!(err.to_string().starts_with("self-intersecting polygon"));
+
+        let doubling_back = vec![(0, 0), (0, 5), (0, 3), (4, 3), (4, 0)];
+        let err = Polygon::new(doubling_back).unwrap_err();
+        assert!(err.to_string().contains("doubles back"));
+
+        let touching = vec![
+            (0, 0),
+            (0, 4),
+            (2, 4),
+            (2, 2),
+            (4, 2),
+            (4, 6),
+            (6, 6),
+            (6, 2),
+            (4, 2),
+        ];
+        assert!(Polygon::new(touching).is_err());
+    }
+
+    /// Small xorshift generator so the random polygons are reproducible.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+    }
+
+    /// Random histogram shaped polygon: columns of random height standing on a common base,
+    /// randomly transposed and reversed so every side and orientation gets exercised. Column
+    /// widths of 1 and 2 tiles make for narrow notches.
+    fn random_polygon(rng: &mut XorShift) -> Vec<Tile> {
+        let base = 12;
+        let mut xs = vec![rng.below(3)];
+        for _ in 0..1 + rng.below(6) {
+            xs.push(xs.last().unwrap() + 1 + rng.below(3));
+        }
+        let mut tops: Vec<usize> = Vec::new();
+        for _ in 1..xs.len() {
+            let mut top = rng.below(base);
+            while tops.last() == Some(&top) {
+                top = rng.below(base);
+            }
+            tops.push(top);
+        }
+
+        let mut corners = vec![(base, xs[0])];
+        for (i, &top) in tops.iter().enumerate() {
+            corners.push((top, xs[i]));
+            corners.push((top, xs[i + 1]));
+        }
+        corners.push((base, *xs.last().unwrap()));
+        if rng.below(2) == 1 {
+            corners.iter_mut().for_each(|(r, c)| std::mem::swap(r, c));
+        }
+        if rng.below(2) == 1 {
+            corners.reverse();
+        }
+        corners
+    }
+
+    /// Covered tiles on the full grid: the boundary, then whatever can't be reached from outside.
+    fn brute_force_grid(corners: &[Tile]) -> Vec<Vec<bool>> {
+        let size = 2 + corners.iter().map(|&(r, c)| r.max(c)).max().unwrap();
+        // shifted by one so there's a ring of outside tiles all around
+        let mut boundary = vec![vec![false; size + 1]; size + 1];
+        let next = corners.iter().skip(1).chain(corners.first());
+        for (&a, &b) in corners.iter().zip(next) {
+            for r in a.0.min(b.0)..=a.0.max(b.0) {
+                for c in a.1.min(b.1)..=a.1.max(b.1) {
+                    boundary[r + 1][c + 1] = true;
+                }
+            }
+        }
+        let mut outside = vec![vec![false; size + 1]; size + 1];
+        let mut stack = vec![(0usize, 0usize)];
+        outside[0][0] = true;
+        while let Some((r, c)) = stack.pop() {
+            let neighbours = [
+                (r.wrapping_sub(1), c),
+                (r + 1, c),
+                (r, c.wrapping_sub(1)),
+                (r, c + 1),
+            ];
+            for (nr, nc) in neighbours {
+                if nr <= size
+                    && nc <= size
+                    && !boundary[nr][nc]
+                    && !outside[nr][nc]
+                {
+                    outside[nr][nc] = true;
+                    stack.push((nr, nc));
+                }
+            }
+        }
+        outside
+            .iter()
+            .skip(1)
+            .map(|row| row.iter().skip(1).map(|&o| !o).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..300 {
+            let corners = random_polygon(&mut rng);
+            let polygon = Polygon::new(corners.clone()).unwrap();
+            let grid = brute_force_grid(&corners);
+            let mut largest = 0;
+            for &a in &corners {
+                for &b in &corners {
+                    let (r0, r1) = (a.0.min(b.0), a.0.max(b.0));
+                    let (c0, c1) = (a.1.min(b.1), a.1.max(b.1));
+                    let covered =
+                        (r0..=r1).all(|r| (c0..=c1).all(|c| grid[r][c]));
+                    assert_eq!(
+                        polygon.contains_rect(a, b),
+                        covered,
+                        "{:?} {:?} in {:?}",
+                        a,
+                        b,
+                        corners
+                    );
+                    if covered {
+                        largest = largest.max((r1 - r0 + 1) * (c1 - c0 + 1));
+                    }
+                }
+            }
+            let (a, b) = polygon.largest_rectangle().unwrap();
+            assert_eq!(area(a, b), largest);
+        }
+    }
+}
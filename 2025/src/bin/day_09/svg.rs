@@ -0,0 +1,102 @@
+//! SVG drawing of the tile polygon, in the puzzle's orientation (x right, y down).
+//!
+//! One SVG unit is one tile, with tile `x,y` centred on the point `x,y`.
+
+use crate::polygon::{Polygon, Tile, area, to_xy};
+use std::fmt::Write;
+
+/// Rectangle to outline on top of the polygon.
+#[derive(Debug)]
+pub struct Highlight {
+    /// Opposite corner tiles
+    pub corners: (Tile, Tile),
+    pub label: &'static str,
+    /// Any SVG colour
+    pub colour: &'static str,
+}
+
+/// Draws the red tile loop over its shaded interior, and outlines each of `highlights` labelled
+/// with its area.
+pub fn render(polygon: &Polygon, highlights: &[Highlight]) -> String {
+    let corners: Vec<(usize, usize)> =
+        polygon.corners().iter().map(|&t| to_xy(t)).collect();
+    let width = corners.iter().map(|p| p.0).max().unwrap_or(0) + 2;
+    let height = corners.iter().map(|p| p.1).max().unwrap_or(0) + 2;
+    // line widths and text in proportion to the drawing, whatever the coordinates
+    let unit = width.max(height) as f64 / 400.0;
+    let points = corners
+        .iter()
+        .map(|(x, y)| format!("{},{}", x, y))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-1 -1 {} {}">"#,
+        width, height
+    )
+    .unwrap();
+    writeln!(svg, r#"<rect x="-1" y="-1" width="{width}" height="{height}" fill="white"/>"#)
+        .unwrap();
+    // a one tile wide stroke through the tile centres covers exactly the boundary tiles
+    writeln!(
+        svg,
+        r##"<polygon points="{points}" fill="#9be39b" stroke="#9be39b" stroke-width="1" stroke-linejoin="miter"/>"##
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<polygon points="{points}" fill="none" stroke="red" stroke-width="{unit}"/>"#
+    )
+    .unwrap();
+    for highlight in highlights {
+        let (a, b) = highlight.corners;
+        let ((ax, ay), (bx, by)) = (to_xy(a), to_xy(b));
+        let (x, y) = (ax.min(bx) as f64 - 0.5, ay.min(by) as f64 - 0.5);
+        let (w, h) = (ax.abs_diff(bx) + 1, ay.abs_diff(by) + 1);
+        writeln!(
+            svg,
+            r#"<rect x="{x}" y="{y}" width="{w}" height="{h}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+            highlight.colour,
+            2.0 * unit
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="{}" fill="{}">{}: {}</text>"#,
+            x + unit,
+            y + 12.0 * unit,
+            10.0 * unit,
+            highlight.colour,
+            highlight.label,
+            area(a, b)
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::polygon::from_xy;
+
+    #[test]
+    fn test_render_orientation() {
+        let corners =
+            vec![from_xy(2, 1), from_xy(6, 1), from_xy(6, 3), from_xy(2, 3)];
+        let polygon = Polygon::new(corners).unwrap();
+        let highlight = Highlight {
+            corners: (from_xy(2, 1), from_xy(6, 3)),
+            label: "best",
+            colour: "blue",
+        };
+        let svg = render(&polygon, &[highlight]);
+        // points as x,y, and a rectangle 5 tiles wide and 3 tiles high
+        assert!(svg.contains(r#"points="2,1 6,1 6,3 2,3""#));
+        assert!(svg.contains(r#"<rect x="1.5" y="0.5" width="5" height="3""#));
+        assert!(svg.contains(">best: 15</text>"));
+    }
+}
@@ -0,0 +1,280 @@
+//! Linear algebra over GF(2), the field of bits with XOR as addition.
+//!
+//! See: <https://en.wikipedia.org/wiki/GF(2)>
+
+use std::{
+    fmt,
+    ops::{BitXorAssign, ControlFlow},
+};
+
+/// Fixed length vector of bits of any width, packed into `u64` words.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    /// All zero vector of `len` bits.
+    pub fn zeros(len: usize) -> Self {
+        Self { words: vec![0; len.div_ceil(64)], len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn get(&self, idx: usize) -> bool {
+        assert!(idx < self.len, "bit {} out of range {}", idx, self.len);
+        (self.words[idx / 64] >> (idx % 64)) & 1 == 1
+    }
+
+    pub fn set(&mut self, idx: usize, value: bool) {
+        assert!(idx < self.len, "bit {} out of range {}", idx, self.len);
+        let mask = 1 << (idx % 64);
+        if value {
+            self.words[idx / 64] |= mask;
+        } else {
+            self.words[idx / 64] &= !mask;
+        }
+    }
+
+    /// Number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Indices of the set bits, ascending.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).filter(|&idx| self.get(idx))
+    }
+}
+
+impl BitXorAssign<&BitVec> for BitVec {
+    fn bitxor_assign(&mut self, rhs: &BitVec) {
+        assert_eq!(self.len, rhs.len, "length mismatch");
+        for (a, b) in self.words.iter_mut().zip(&rhs.words) {
+            *a ^= b;
+        }
+    }
+}
+
+/// Bits as `0` and `1`, index 0 first.
+impl fmt::Display for BitVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (0..self.len)
+            .try_for_each(|idx| write!(f, "{}", u8::from(self.get(idx))))
+    }
+}
+
+/// Every solution of a linear system: `particular` plus any sum of `basis` vectors.
+#[derive(Debug, Clone)]
+pub struct SolutionSpace {
+    pub particular: BitVec,
+    /// Basis of the null space, one vector per free variable
+    pub basis: Vec<BitVec>,
+}
+
+/// Most free variables [`SolutionSpace`] will enumerate: 2^32 solutions is already hours of
+/// walking.
+pub const MAX_FREE_VARIABLES: usize = 32;
+
+impl SolutionSpace {
+    /// Calls `visit` with every solution, in Gray code order so each step is a single XOR, until
+    /// it breaks.
+    ///
+    /// # Errors
+    ///
+    /// If there are more than [`MAX_FREE_VARIABLES`] free variables.
+    fn walk(
+        &self,
+        mut visit: impl FnMut(&BitVec) -> ControlFlow<()>,
+    ) -> anyhow::Result<()> {
+        if self.basis.len() > MAX_FREE_VARIABLES {
+            anyhow::bail!(
+                "{} free variables, too many to try all 2^{} solutions (at most {})",
+                self.basis.len(),
+                self.basis.len(),
+                MAX_FREE_VARIABLES
+            );
+        }
+        let mut current = self.particular.clone();
+        if visit(&current).is_break() {
+            return Ok(());
+        }
+        for step in 1..1u64 << self.basis.len() {
+            current ^= &self.basis[step.trailing_zeros() as usize];
+            if visit(&current).is_break() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// All solutions with the fewest set bits.
+    ///
+    /// Exponential in the number of free variables only.
+    ///
+    /// # Errors
+    ///
+    /// If there are more than [`MAX_FREE_VARIABLES`] free variables.
+    pub fn min_weight_solutions(&self) -> anyhow::Result<Vec<BitVec>> {
+        let mut best_weight = usize::MAX;
+        let mut best = Vec::new();
+        self.walk(|current| {
+            let weight = current.count_ones();
+            if weight < best_weight {
+                best_weight = weight;
+                best.clear();
+            }
+            if weight == best_weight {
+                best.push(current.clone());
+            }
+            ControlFlow::Continue(())
+        })?;
+        Ok(best)
+    }
+
+    /// The first solution met with the fewest set bits, without collecting the others. Stops
+    /// early on the all zero solution.
+    ///
+    /// # Errors
+    ///
+    /// If there are more than [`MAX_FREE_VARIABLES`] free variables.
+    pub fn min_weight_solution(&self) -> anyhow::Result<BitVec> {
+        let mut best = self.particular.clone();
+        let mut best_weight = best.count_ones();
+        self.walk(|current| {
+            let weight = current.count_ones();
+            if weight < best_weight {
+                best_weight = weight;
+                best = current.clone();
+            }
+            if best_weight == 0 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })?;
+        Ok(best)
+    }
+}
+
+/// Solves `Σ x[j] * columns[j] = target` for the bit vector `x`, or `None` if there is no
+/// solution.
+///
+/// Every column must be as long as `target`.
+pub fn solve(columns: &[BitVec], target: &BitVec) -> Option<SolutionSpace> {
+    let m = columns.len();
+    // one equation per bit of the target: its row of coefficients plus the target bit in
+    // position m
+    let mut rows: Vec<BitVec> = (0..target.len())
+        .map(|i| {
+            let mut row = BitVec::zeros(m + 1);
+            for (j, column) in columns.iter().enumerate() {
+                row.set(j, column.get(i));
+            }
+            row.set(m, target.get(i));
+            row
+        })
+        .collect();
+
+    // Gauss-Jordan elimination to reduced row echelon form
+    let mut pivots = Vec::new();
+    for col in 0..m {
+        let rank = pivots.len();
+        let Some(found) = (rank..rows.len()).find(|&r| rows[r].get(col))
+        else {
+            continue;
+        };
+        rows.swap(rank, found);
+        let pivot_row = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != rank && row.get(col) {
+                *row ^= &pivot_row;
+            }
+        }
+        pivots.push(col);
+    }
+
+    // leftover rows read 0 = target bit
+    if rows[pivots.len()..].iter().any(|row| row.get(m)) {
+        return None;
+    }
+
+    let mut particular = BitVec::zeros(m);
+    for (row, &col) in rows.iter().zip(&pivots) {
+        particular.set(col, row.get(m));
+    }
+    let basis = (0..m)
+        .filter(|col| !pivots.contains(col))
+        .map(|free| {
+            let mut vector = BitVec::zeros(m);
+            vector.set(free, true);
+            for (row, &col) in rows.iter().zip(&pivots) {
+                vector.set(col, row.get(free));
+            }
+            vector
+        })
+        .collect();
+    Some(SolutionSpace { particular, basis })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bits(s: &str) -> BitVec {
+        let mut v = BitVec::zeros(s.len());
+        s.chars().enumerate().for_each(|(i, ch)| v.set(i, ch == '1'));
+        v
+    }
+
+    #[test]
+    fn test_bitvec_beyond_one_word() {
+        let mut v = BitVec::zeros(130);
+        v.set(0, true);
+        v.set(64, true);
+        v.set(129, true);
+        assert_eq!(v.ones().collect::<Vec<_>>(), vec![0, 64, 129]);
+        let mut w = v.clone();
+        w ^= &v;
+        assert_eq!(w, BitVec::zeros(130));
+        assert_eq!(v.count_ones(), 3);
+        assert_eq!(bits("0110").to_string(), "0110");
+    }
+
+    #[test]
+    fn test_solve_and_min_weight() {
+        // x0 + x1 = 1, x1 + x2 = 1: solutions 010 and 101
+        let columns = [bits("10"), bits("11"), bits("01")];
+        let space = solve(&columns, &bits("11")).unwrap();
+        assert_eq!(space.basis.len(), 1);
+        assert_eq!(space.min_weight_solutions().unwrap(), vec![bits("010")]);
+        assert_eq!(space.min_weight_solution().unwrap(), bits("010"));
+
+        // two ways of a single button
+        let columns = [bits("11"), bits("11"), bits("10")];
+        let space = solve(&columns, &bits("11")).unwrap();
+        let mut best = space.min_weight_solutions().unwrap();
+        best.sort_by_key(|v| v.to_string());
+        assert_eq!(best, vec![bits("010"), bits("100")]);
+        assert!(best.contains(&space.min_weight_solution().unwrap()));
+
+        assert!(solve(&[bits("11")], &bits("10")).is_none());
+    }
+
+    #[test]
+    fn test_too_many_free_variables() {
+        // 70 copies of the same one bit button: 69 free variables
+        let columns = vec![bits("1"); 70];
+        let space = solve(&columns, &bits("1")).unwrap();
+        assert_eq!(space.basis.len(), 69);
+        let err = space.min_weight_solution().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "69 free variables, too many to try all 2^69 solutions (at most 32)"
+        );
+        assert!(space.min_weight_solutions().is_err());
+    }
+}
@@ -0,0 +1,502 @@
+//! Exact integer linear programming for small systems: minimise `Σx` subject to `A·x = b` over
+//! non-negative integers, for a matrix `A` with non-negative entries.
+//!
+//! Gaussian elimination over the rationals expresses the pivot variables in terms of the free
+//! ones, then branch-and-bound searches the free variables within the bounds implied by `b`.
+//! All arithmetic is exact and checked: a system whose numbers outgrow `i128` is an error, never
+//! a wrong answer.
+//!
+//! See: <https://en.wikipedia.org/wiki/Branch_and_bound>
+
+/// Exact fraction, always in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rational {
+    num: i128,
+    den: i128,
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+/// Error for an intermediate value that doesn't fit in an `i128`.
+fn overflow(stage: &str) -> anyhow::Error {
+    anyhow::anyhow!("exact arithmetic overflows i128 while {}", stage)
+}
+
+/// Every arithmetic operation is checked, `None` when a numerator or denominator would leave
+/// the `i128` range.
+impl Rational {
+    fn new(num: i128, den: i128) -> Option<Self> {
+        assert!(den != 0, "zero denominator");
+        let g = gcd(num, den).checked_mul(den.signum())?;
+        Some(Self { num: num / g, den: den / g })
+    }
+
+    fn is_zero(self) -> bool {
+        self.num == 0
+    }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        let num = self
+            .num
+            .checked_mul(rhs.den)?
+            .checked_add(rhs.num.checked_mul(self.den)?)?;
+        Self::new(num, self.den.checked_mul(rhs.den)?)
+    }
+
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(Self { num: rhs.num.checked_neg()?, den: rhs.den })
+    }
+
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        // cancel across first so products only overflow when the result itself would
+        let g1 = gcd(self.num, rhs.den).max(1);
+        let g2 = gcd(rhs.num, self.den).max(1);
+        Self::new(
+            (self.num / g1).checked_mul(rhs.num / g2)?,
+            (self.den / g2).checked_mul(rhs.den / g1)?,
+        )
+    }
+
+    fn checked_div(self, rhs: Self) -> Option<Self> {
+        assert!(!rhs.is_zero(), "division by zero");
+        let recip = Self::new(rhs.den, rhs.num)?;
+        self.checked_mul(recip)
+    }
+}
+
+impl From<u64> for Rational {
+    fn from(value: u64) -> Self {
+        Self { num: value.into(), den: 1 }
+    }
+}
+
+/// A pivot variable solved for in terms of the free variables:
+/// `x[var] = rhs - Σ coefs[f] * x[free[f]]`.
+#[derive(Debug)]
+struct PivotRow {
+    var: usize,
+    rhs: Rational,
+    /// Coefficient of each free variable
+    coefs: Vec<Rational>,
+}
+
+/// Reduced row echelon form of `[A | b]`, or `None` if the system has no solution at all.
+///
+/// # Errors
+///
+/// If a fraction along the way doesn't fit in `i128`.
+fn eliminate(
+    a: &[Vec<u64>],
+    b: &[u64],
+) -> anyhow::Result<Option<(Vec<PivotRow>, Vec<usize>)>> {
+    let fail = || overflow("eliminating");
+    let n = a.first().map_or(0, Vec::len);
+    let mut rows: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &rhs)| {
+            row.iter().chain([&rhs]).map(|&v| Rational::from(v)).collect()
+        })
+        .collect();
+
+    let mut pivots = Vec::new();
+    let mut free = Vec::new();
+    for col in 0..n {
+        let rank = pivots.len();
+        let Some(found) =
+            (rank..rows.len()).find(|&r| !rows[r][col].is_zero())
+        else {
+            free.push(col);
+            continue;
+        };
+        rows.swap(rank, found);
+        let pivot = rows[rank][col];
+        for v in rows[rank].iter_mut() {
+            *v = v.checked_div(pivot).ok_or_else(fail)?;
+        }
+        let pivot_row = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r == rank || factor.is_zero() {
+                continue;
+            }
+            for (v, &p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *v = factor
+                    .checked_mul(p)
+                    .and_then(|fp| v.checked_sub(fp))
+                    .ok_or_else(fail)?;
+            }
+        }
+        pivots.push(col);
+    }
+
+    // leftover rows read 0 = rhs
+    if rows[pivots.len()..].iter().any(|row| !row[n].is_zero()) {
+        return Ok(None);
+    }
+    let pivot_rows = pivots
+        .iter()
+        .zip(&rows)
+        .map(|(&var, row)| PivotRow {
+            var,
+            rhs: row[n],
+            coefs: free.iter().map(|&f| row[f]).collect(),
+        })
+        .collect();
+    Ok(Some((pivot_rows, free)))
+}
+
+/// A [`PivotRow`] scaled to integers: `scale * x[var] = rhs - Σ coefs[f] * x[free[f]]`.
+#[derive(Debug)]
+struct ScaledRow {
+    var: usize,
+    scale: i128,
+    rhs: i128,
+    coefs: Vec<i128>,
+}
+
+impl TryFrom<&PivotRow> for ScaledRow {
+    type Error = anyhow::Error;
+
+    fn try_from(row: &PivotRow) -> anyhow::Result<Self> {
+        let fail = || overflow("scaling rows to integers");
+        let scale = row
+            .coefs
+            .iter()
+            .try_fold(row.rhs.den, |acc, c| lcm(acc, c.den))
+            .ok_or_else(fail)?;
+        let scaled = |v: Rational| v.num.checked_mul(scale / v.den);
+        Ok(Self {
+            var: row.var,
+            scale,
+            rhs: scaled(row.rhs).ok_or_else(fail)?,
+            coefs: row
+                .coefs
+                .iter()
+                .map(|&c| scaled(c).ok_or_else(fail))
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+}
+
+fn lcm(a: i128, b: i128) -> Option<i128> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Inclusive range of values a free variable can still take.
+type Bounds = (i128, i128);
+
+/// Branch-and-bound over the free variables, in integer arithmetic throughout.
+///
+/// Every node narrows the free variables' bounds by propagating the pivot rows, then branches on
+/// the free variable with the fewest values left. The objective is tracked multiplied by
+/// `scale`, a multiple of every row's scale, so it stays integral too.
+///
+/// [`Search::new`] checks up front that every sum the search forms fits in `i128`, so the search
+/// itself can use plain arithmetic.
+struct Search {
+    rows: Vec<ScaledRow>,
+    free: Vec<usize>,
+    /// Largest useful value of every variable
+    upper: Vec<u64>,
+    scale: i128,
+    /// Scaled objective with every free variable at 0
+    base_cost: i128,
+    /// Change of the scaled objective per unit of each free variable, once pivots are
+    /// substituted
+    costs: Vec<i128>,
+    best: Option<(u64, Vec<u64>)>,
+}
+
+impl Search {
+    /// # Errors
+    ///
+    /// If the scaled rows or objective are too large for the search to stay within `i128`.
+    fn new(
+        pivots: &[PivotRow],
+        free: Vec<usize>,
+        upper: Vec<u64>,
+    ) -> anyhow::Result<Self> {
+        let fail = || overflow("setting up the search");
+        let rows: Vec<ScaledRow> = pivots
+            .iter()
+            .map(ScaledRow::try_from)
+            .collect::<anyhow::Result<_>>()?;
+        let scale = rows
+            .iter()
+            .try_fold(1, |acc, row| lcm(acc, row.scale))
+            .ok_or_else(fail)?;
+        // scale * Σx = Σ_k (scale / scale_k) * (rhs_k - Σ coefs_k * x_free) + scale * Σ x_free
+        let base_cost = rows
+            .iter()
+            .try_fold(0i128, |acc, row| {
+                acc.checked_add((scale / row.scale).checked_mul(row.rhs)?)
+            })
+            .ok_or_else(fail)?;
+        let costs = (0..free.len())
+            .map(|f| {
+                rows.iter()
+                    .try_fold(scale, |acc, row| {
+                        acc.checked_sub(
+                            (scale / row.scale).checked_mul(row.coefs[f])?,
+                        )
+                    })
+                    .ok_or_else(fail)
+            })
+            .collect::<anyhow::Result<Vec<i128>>>()?;
+
+        // the largest magnitude a row or objective sum can reach with every variable in
+        // range, with room for the few of them added together in `propagate`
+        let max_upper = i128::from(upper.iter().copied().max().unwrap_or(0));
+        let reach = |constant: i128, coefs: &[i128], extra: i128| {
+            coefs
+                .iter()
+                .try_fold(constant.checked_abs()?, |acc, &c| {
+                    acc.checked_add(c.checked_abs()?.checked_mul(max_upper)?)
+                })?
+                .checked_add(extra)?
+                .checked_mul(4)
+        };
+        let total_upper = upper.iter().map(|&u| i128::from(u)).sum::<i128>();
+        for row in &rows {
+            let top = row.scale.checked_mul(max_upper).ok_or_else(fail)?;
+            reach(row.rhs, &row.coefs, top).ok_or_else(fail)?;
+        }
+        reach(base_cost, &costs, 0).ok_or_else(fail)?;
+        total_upper.checked_mul(scale).ok_or_else(fail)?;
+
+        Ok(Self { rows, free, upper, scale, base_cost, costs, best: None })
+    }
+
+    /// Smallest scaled objective within `bounds`.
+    fn lower_bound(&self, bounds: &[Bounds]) -> i128 {
+        self.base_cost
+            + self
+                .costs
+                .iter()
+                .zip(bounds)
+                .map(|(&c, &(lo, hi))| (c * lo).min(c * hi))
+                .sum::<i128>()
+    }
+
+    /// Whether a scaled objective of at least `cost` can't beat the best solution so far.
+    fn is_dominated(&self, cost: i128) -> bool {
+        self.best
+            .as_ref()
+            .is_some_and(|(best, _)| cost >= i128::from(*best) * self.scale)
+    }
+
+    /// Narrows `bounds` until every value left could keep each pivot variable within
+    /// `0..=upper`, as far as the other bounds tell. Returns `false` if a row can't be met.
+    fn propagate(&self, bounds: &mut [Bounds]) -> bool {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for row in &self.rows {
+                // the scaled pivot, rhs - Σ coefs * x, must land in 0..=top
+                let top = row.scale * i128::from(self.upper[row.var]);
+                let terms = |(&coef, &(lo, hi)): (&i128, &Bounds)| {
+                    (
+                        (-coef * lo).min(-coef * hi),
+                        (-coef * lo).max(-coef * hi),
+                    )
+                };
+                let (min, max) = row
+                    .coefs
+                    .iter()
+                    .zip(&*bounds)
+                    .map(terms)
+                    .fold((row.rhs, row.rhs), |(min, max), (lo, hi)| {
+                        (min + lo, max + hi)
+                    });
+                if max < 0 || min > top {
+                    return false;
+                }
+                for (&coef, bound) in row.coefs.iter().zip(bounds.iter_mut()) {
+                    if coef == 0 {
+                        continue;
+                    }
+                    // the row without this variable, which then has to make up the difference
+                    let (lo, hi) = terms((&coef, bound));
+                    let (rest_min, rest_max) = (min - lo, max - hi);
+                    let (x_lo, x_hi) = if coef > 0 {
+                        (
+                            div_ceil(rest_min - top, coef),
+                            div_floor(rest_max, coef),
+                        )
+                    } else {
+                        (
+                            div_ceil(rest_max, coef),
+                            div_floor(rest_min - top, coef),
+                        )
+                    };
+                    let narrowed = (bound.0.max(x_lo), bound.1.min(x_hi));
+                    if narrowed.0 > narrowed.1 {
+                        return false;
+                    }
+                    if narrowed != *bound {
+                        // min and max stay valid, if looser, until the next pass
+                        *bound = narrowed;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn search(&mut self, mut bounds: Vec<Bounds>) {
+        if !self.propagate(&mut bounds)
+            || self.is_dominated(self.lower_bound(&bounds))
+        {
+            return;
+        }
+        let Some(f) = (0..bounds.len())
+            .filter(|&f| bounds[f].0 < bounds[f].1)
+            .min_by_key(|&f| bounds[f].1 - bounds[f].0)
+        else {
+            self.record(&bounds);
+            return;
+        };
+
+        // try the cheap end first, so the bound cuts the rest of the loop short
+        let (lo, hi) = bounds[f];
+        for step in 0..=hi - lo {
+            let x = if self.costs[f] >= 0 { lo + step } else { hi - step };
+            let mut next = bounds.clone();
+            next[f] = (x, x);
+            if self.is_dominated(self.lower_bound(&next)) {
+                break;
+            }
+            self.search(next);
+        }
+    }
+
+    /// Keeps the fully assigned solution if all pivots came out as non-negative integers.
+    fn record(&mut self, bounds: &[Bounds]) {
+        let mut solution = vec![0; self.upper.len()];
+        for (&var, &(x, _)) in self.free.iter().zip(bounds) {
+            solution[var] = x as u64;
+        }
+        for row in &self.rows {
+            let value = row.rhs
+                - row
+                    .coefs
+                    .iter()
+                    .zip(bounds)
+                    .map(|(&coef, &(x, _))| coef * x)
+                    .sum::<i128>();
+            if value % row.scale != 0 {
+                return;
+            }
+            match u64::try_from(value / row.scale) {
+                Ok(x) if x <= self.upper[row.var] => solution[row.var] = x,
+                _ => return,
+            }
+        }
+        let total = solution.iter().sum();
+        if self.best.as_ref().is_none_or(|(best, _)| total < *best) {
+            self.best = Some((total, solution));
+        }
+    }
+}
+
+/// `a / b` rounded down, for `b != 0`.
+fn div_floor(a: i128, b: i128) -> i128 {
+    let q = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) { q - 1 } else { q }
+}
+
+/// `a / b` rounded up, for `b != 0`.
+fn div_ceil(a: i128, b: i128) -> i128 {
+    -div_floor(-a, b)
+}
+
+/// Non-negative integer `x` with `A·x = b` and the smallest `Σx`, or `None` if there is none.
+///
+/// `a` is given row by row and every row must have the same length.
+///
+/// # Errors
+///
+/// If exact arithmetic on the system would overflow `i128`.
+pub fn minimise_sum(
+    a: &[Vec<u64>],
+    b: &[u64],
+) -> anyhow::Result<Option<Vec<u64>>> {
+    let n = a.first().map_or(0, Vec::len);
+    // a variable can't exceed any target it contributes to, and is useless if it contributes
+    // to none
+    let upper: Vec<u64> = (0..n)
+        .map(|j| {
+            a.iter()
+                .zip(b)
+                .filter(|(row, _)| row[j] > 0)
+                .map(|(row, &target)| target / row[j])
+                .min()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let Some((pivots, free)) = eliminate(a, b)? else {
+        return Ok(None);
+    };
+    let bounds = free.iter().map(|&f| (0, i128::from(upper[f]))).collect();
+    let mut search = Search::new(&pivots, free, upper)?;
+    search.search(bounds);
+    Ok(search.best.map(|(_, solution)| solution))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rational_arithmetic() {
+        let r = |num, den| Rational::new(num, den).unwrap();
+        let half = r(2, 4);
+        assert_eq!(half, r(-1, -2));
+        assert_eq!(half.checked_add(r(1, 3)), Some(r(5, 6)));
+        assert_eq!(half.checked_sub(r(1, 3)), Some(r(1, 6)));
+        assert_eq!(half.checked_div(r(-3, 1)), Some(r(-1, 6)));
+        assert_eq!(
+            half.checked_mul(Rational::from(4)),
+            Some(Rational::from(2))
+        );
+
+        let huge = r(i128::MAX, 1);
+        assert_eq!(huge.checked_add(Rational::from(1)), None);
+        assert_eq!(huge.checked_mul(Rational::from(2)), None);
+        // cancelling first keeps products that fit from overflowing
+        assert_eq!(huge.checked_mul(r(1, i128::MAX)), Some(Rational::from(1)));
+    }
+
+    #[test]
+    fn test_overflow_is_an_error() {
+        // eliminating this pair of nearly equal rows needs a numerator near 2^128
+        let m = u64::MAX;
+        let a = vec![vec![m, m - 1], vec![m - 2, m - 3]];
+        let err = minimise_sum(&a, &[m, m]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "exact arithmetic overflows i128 while eliminating"
+        );
+    }
+
+    #[test]
+    fn test_minimise_sum() {
+        // x0 + x1 = 3, x1 + x2 = 5: best is x1 = 3
+        let a = vec![vec![1, 1, 0], vec![0, 1, 1]];
+        assert_eq!(minimise_sum(&a, &[3, 5]).unwrap(), Some(vec![0, 3, 2]));
+        // 2 * x0 = 3 has no integer solution
+        assert_eq!(minimise_sum(&[vec![2]], &[3]).unwrap(), None);
+        // x0 = 1 and x0 = 2 is inconsistent
+        assert_eq!(minimise_sum(&[vec![1], vec![1]], &[1, 2]).unwrap(), None);
+        // a variable that touches nothing is never used
+        assert_eq!(
+            minimise_sum(&[vec![1, 0]], &[4]).unwrap(),
+            Some(vec![4, 0])
+        );
+    }
+}
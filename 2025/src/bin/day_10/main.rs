@@ -0,0 +1,602 @@
+// See: https://www.reddit.com/r/adventofcode/comments/1pp98cr/2025_day_10_part_2_solution_without_using_a_3rd/
+use anyhow::Context;
+use aoc_common::parallel;
+use clap::Parser;
+use std::{
+    collections::{HashSet, VecDeque},
+    str::FromStr,
+};
+
+use gf2::BitVec;
+use plan::PressPlan;
+
+mod gf2;
+mod ilp;
+mod parse;
+mod plan;
+
+#[derive(Debug)]
+struct Machine {
+    /// Target light pattern, bit `i` for light `i`
+    target: BitVec,
+    /// Button wiring schematics: the lights each button toggles
+    toggles: Vec<BitVec>,
+    /// Joltage requirements
+    joltages: Vec<u16>,
+}
+
+impl Machine {
+    /// First attempt: BFS over light states, exponential in the number of lights
+    #[allow(dead_code)]
+    fn min_button_presses_match_target_bfs(&self) -> anyhow::Result<usize> {
+        let mut visited: HashSet<BitVec> = HashSet::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((0usize, BitVec::zeros(self.target.len())));
+
+        while let Some(entry) = queue.pop_front() {
+            let (ops, curr) = entry;
+            if curr == self.target {
+                return Ok(ops);
+            }
+            if visited.contains(&curr) {
+                continue; // were able to reach in less ops
+            }
+            visited.insert(curr.clone());
+            for toggle in &self.toggles {
+                let mut curr = curr.clone();
+                curr ^= toggle;
+                queue.push_back((ops + 1, curr));
+            }
+        }
+
+        anyhow::bail!("Target not reachable with given toggles: {}", self)
+    }
+
+    /// Every way of pressing each button at most once that reaches the target lights.
+    ///
+    /// Pressing a button twice undoes it, so only which buttons are pressed matters: that's a
+    /// linear system over GF(2), one equation per light.
+    fn solution_space(&self) -> anyhow::Result<gf2::SolutionSpace> {
+        gf2::solve(&self.toggles, &self.target).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Target not reachable with given toggles: {}",
+                self
+            )
+        })
+    }
+
+    /// Every set of buttons that reaches the target lights with the fewest presses, as
+    /// ascending button indices.
+    fn optimal_button_sets(&self) -> anyhow::Result<Vec<Vec<usize>>> {
+        let space = self.solution_space()?;
+        Ok(space
+            .min_weight_solutions()
+            .with_context(|| format!("Lights of {}", self))?
+            .iter()
+            .map(|presses| presses.ones().collect())
+            .collect())
+    }
+
+    fn min_button_presses_match_target(&self) -> anyhow::Result<PressPlan> {
+        let space = self.solution_space()?;
+        let best = space
+            .min_weight_solution()
+            .with_context(|| format!("Lights of {}", self))?;
+        let mut presses = vec![0; self.toggles.len()];
+        for button in best.ones() {
+            presses[button] = 1;
+        }
+        Ok(PressPlan { presses })
+    }
+
+    /// First attempt: too slow on large input
+    #[allow(dead_code)]
+    fn min_button_presses_match_joltages_naive_bfs(
+        &self,
+    ) -> anyhow::Result<usize> {
+        let n = self.joltages.len();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new();
+
+        queue.push_back((0usize, vec![0u16; n]));
+        while let Some((presses, current)) = queue.pop_front() {
+            if current == self.joltages {
+                return Ok(presses);
+            }
+
+            if visited.contains(&current) {
+                continue;
+            }
+            visited.insert(current.clone());
+
+            for toggle in &self.toggles {
+                let mut next = current.clone();
+                toggle.ones().for_each(|i| next[i] += 1);
+
+                // early stop if any joltage exceeds specified
+                if next
+                    .iter()
+                    .zip(&self.joltages)
+                    .all(|(curr, target)| curr <= target)
+                {
+                    queue.push_back((presses + 1, next));
+                }
+            }
+        }
+        anyhow::bail!("Target not reachable with given toggles: {}", self)
+    }
+
+    /// Second attempt: BFS over joltages packed into a `u64`, limited by how many fit
+    #[allow(dead_code)]
+    fn min_button_presses_match_joltages_packed_bfs(
+        &self,
+    ) -> anyhow::Result<usize> {
+        let n = self.joltages.len();
+        let max_joltage = *self.joltages.iter().max().expect("no joltages");
+
+        // use extra bit to prevent overflow in search space
+        let bits_per_value =
+            ((16 - max_joltage.leading_zeros()) as usize).max(5);
+
+        if n * bits_per_value > 64 {
+            anyhow::bail!("Can't pack joltages into u64: {:?}", self.joltages);
+        }
+
+        let target_limits: Vec<u64> =
+            self.joltages.iter().map(|&j| j as u64).collect();
+
+        #[cfg(test)]
+        {
+            eprintln!(
+                "n={}, max_joltage={}, bits_per_value={}",
+                n, max_joltage, bits_per_value
+            );
+            eprintln!("Target joltages: {:?}", self.joltages);
+        }
+
+        let value_mask = (1u64 << bits_per_value) - 1;
+
+        let target_state =
+            self.joltages.iter().enumerate().fold(0u64, |acc, (i, &j)| {
+                acc | (j as u64) << (i * bits_per_value)
+            });
+
+        let toggle_deltas: Vec<u64> = self
+            .toggles
+            .iter()
+            .map(|toggle| {
+                toggle.ones().fold(0u64, |delta, i| {
+                    delta | 1u64 << (i * bits_per_value)
+                })
+            })
+            .collect();
+
+        let add_packed = |state: u64, delta: u64| -> Option<u64> {
+            let mut res = 0u64;
+            for (i, limit) in target_limits.iter().enumerate().take(n) {
+                let shift = i * bits_per_value;
+                let a = (state >> shift) & value_mask;
+                let b = (delta >> shift) & value_mask;
+                let sum = a + b;
+                if sum > *limit {
+                    return None;
+                }
+                res |= sum << shift;
+            }
+            Some(res)
+        };
+
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::new(); // (ops, state) entries
+        queue.push_back((0usize, 0u64));
+        while let Some((presses, state)) = queue.pop_front() {
+            if state == target_state {
+                return Ok(presses);
+            }
+            if !visited.insert(state) {
+                continue;
+            }
+            for &delta in &toggle_deltas {
+                if let Some(next) = add_packed(state, delta) {
+                    queue.push_back((presses + 1, next));
+                }
+            }
+        }
+
+        anyhow::bail!("Target not reachable with given toggles: {}", self)
+    }
+
+    /// `matrix[i][j]` -> 1 if button `j` increments joltage counter `i`
+    fn joltage_matrix(&self) -> Vec<Vec<u64>> {
+        (0..self.joltages.len())
+            .map(|i| {
+                self.toggles
+                    .iter()
+                    .map(|toggle| u64::from(toggle.get(i)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Solves the presses as an integer linear program: one variable per button, one equation
+    /// per joltage counter.
+    fn min_button_presses_match_joltages(&self) -> anyhow::Result<PressPlan> {
+        let targets: Vec<u64> =
+            self.joltages.iter().map(|&j| j.into()).collect();
+        let presses = ilp::minimise_sum(&self.joltage_matrix(), &targets)
+            .with_context(|| format!("Solving joltages for {}", self))?
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Target not reachable with given toggles: {}",
+                    self
+                )
+            })?;
+        Ok(PressPlan { presses })
+    }
+}
+
+/// The machine in its puzzle input syntax.
+impl std::fmt::Display for Machine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_diagram(&self.target))?;
+        for toggle in &self.toggles {
+            write!(f, " {}", format_wiring(toggle))?;
+        }
+        write!(f, " {}", format_joltages(&self.joltages))
+    }
+}
+
+/// Lights as in a light diagram, e.g. `[.##.]`.
+fn format_diagram(lights: &BitVec) -> String {
+    let lights: String = (0..lights.len())
+        .map(|i| if lights.get(i) { '#' } else { '.' })
+        .collect();
+    format!("[{}]", lights)
+}
+
+/// The lights a button toggles as in a wiring schematic, e.g. `(0,2)`.
+fn format_wiring(toggle: &BitVec) -> String {
+    let lights: Vec<String> = toggle.ones().map(|i| i.to_string()).collect();
+    format!("({})", lights.join(","))
+}
+
+/// Joltages as in a joltage requirement, e.g. `{3,5,4,7}`.
+fn format_joltages<T: ToString>(joltages: &[T]) -> String {
+    let joltages: Vec<String> = joltages.iter().map(T::to_string).collect();
+    format!("{{{}}}", joltages.join(","))
+}
+
+fn parse_input(contents: &str) -> anyhow::Result<Vec<Machine>> {
+    contents
+        .trim()
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            Machine::from_str(line)
+                .with_context(|| format!("line {}: {}", idx + 1, line))
+        })
+        .collect::<anyhow::Result<_>>()
+}
+
+/// Compute the minimum number of presses of the toggles to achieve the machine's lighting diagram.
+/// Initially, all lights are off.
+///
+///  # Errors
+///
+/// If the end state is not possible, or a machine has more free buttons than
+/// [`gf2::MAX_FREE_VARIABLES`] to try every set of.
+fn compute_min_button_presses(machines: &[Machine]) -> anyhow::Result<usize> {
+    let plans = parallel::map(machines, |machine| {
+        let plan = machine.min_button_presses_match_target()?;
+        plan.check_lights(machine)?;
+        Ok(plan)
+    });
+    plans.into_iter().try_fold(0usize, |acc, plan: anyhow::Result<_>| {
+        Ok(acc + plan?.total() as usize)
+    })
+}
+
+/// Sum the number of minimum presses required for each machine to achieve the exact its exact
+/// joltages, where each toggle represents the lights whose joltages will be incremented by 1.
+/// Initially, all lights are off.
+///
+/// # Errors
+///
+/// If the end state is not possible.
+fn compute_min_presses_to_match_joltages(
+    machines: &[Machine],
+) -> anyhow::Result<usize> {
+    let plans = parallel::map(machines, |machine| {
+        let plan = machine.min_button_presses_match_joltages()?;
+        plan.check_joltages(machine)?;
+        Ok(plan)
+    });
+    plans.into_iter().try_fold(0usize, |acc, plan: anyhow::Result<_>| {
+        Ok(acc + plan?.total() as usize)
+    })
+}
+
+#[derive(Parser)]
+struct Args {
+    /// Print every machine's button presses step by step, for the lights and the joltages.
+    #[arg(long)]
+    animate: bool,
+    /// List every set of buttons that sets each machine's lights with the fewest presses. Tries
+    /// all 2^k button sets for k free buttons, so machines with more than 32 are an error.
+    #[arg(long)]
+    all_optimal: bool,
+    /// Worker threads for solving machines, 0 for one per CPU. More than one needs the
+    /// `parallel` feature.
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    parallel::set_threads(args.threads).map_err(anyhow::Error::msg)?;
+    let contents = std::fs::read_to_string("./data/day-10-input.txt")?;
+    let machines = parse_input(&contents)?;
+
+    if args.animate {
+        for machine in &machines {
+            let lights = machine.min_button_presses_match_target()?;
+            println!("{}", lights.animate(machine));
+            let joltages = machine.min_button_presses_match_joltages()?;
+            println!("{}", joltages.animate(machine));
+        }
+    }
+
+    if args.all_optimal {
+        for (idx, machine) in machines.iter().enumerate() {
+            let sets: Vec<String> = machine
+                .optimal_button_sets()?
+                .iter()
+                .map(|set| format!("{:?}", set))
+                .collect();
+            println!("Machine {}: {}", idx + 1, sets.join(" "));
+        }
+    }
+
+    /* Part 1 */
+    println!("Part 1: {}", compute_min_button_presses(&machines)?);
+
+    /* Part 2 */
+    println!("Part 2: {}", compute_min_presses_to_match_joltages(&machines)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_part1_example_machine1() {
+        let input = "
+[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+            ";
+        let machines = parse_input(input).unwrap();
+        // Press last 2 buttons:
+        //
+        //       start: [....]
+        // press (0,2): [#.#.]
+        // press (0,1): [.##.] == [.##.]
+        assert_eq!(compute_min_button_presses(&machines).unwrap(), 2)
+    }
+
+    #[test]
+    fn test_part1_example_machine2() {
+        let input = "
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+            ";
+        let machines = parse_input(input).unwrap();
+        // Press ((0,4), (0,1,2), and (1,2,3,4))
+        //
+        //     start:   [.....]
+        //     (0,4):   [#...#]
+        //   (0,1,2):   [.##.#]
+        // (1,2,3,4):   [...#.] == [...#.]
+        assert_eq!(compute_min_button_presses(&machines).unwrap(), 3)
+    }
+
+    #[test]
+    fn test_part1_example_machine3() {
+        let input = "
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
+            ";
+        let machines = parse_input(input).unwrap();
+        // Press (0,3,4) and (0,1,2,4,5)
+        //
+        //       start: [......]
+        //     (0,3,4): [#..##.]
+        // (0,1,2,4,5): [.###.#] == [.###.#]
+        assert_eq!(compute_min_button_presses(&machines).unwrap(), 2)
+    }
+
+    #[test]
+    fn test_part1_example() {
+        let input = "
+[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
+            ";
+        let machines = parse_input(input).unwrap();
+        assert_eq!(compute_min_button_presses(&machines).unwrap(), 7)
+    }
+
+    #[test]
+    fn test_part2_example_machine1() {
+        let input = "
+[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+            ";
+        let machines = parse_input(input).unwrap();
+        assert_eq!(
+            compute_min_presses_to_match_joltages(&machines).unwrap(),
+            10
+        )
+    }
+
+    #[test]
+    fn test_part2_example_machine2() {
+        let input = "
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+            ";
+        let machines = parse_input(input).unwrap();
+        assert_eq!(
+            compute_min_presses_to_match_joltages(&machines).unwrap(),
+            12
+        )
+    }
+
+    #[test]
+    fn test_part2_example_machine3() {
+        let input = "
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
+            ";
+        let machines = parse_input(input).unwrap();
+        assert_eq!(
+            compute_min_presses_to_match_joltages(&machines).unwrap(),
+            11
+        )
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let input = "
+[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
+            ";
+        let machines = parse_input(input).unwrap();
+        assert_eq!(
+            compute_min_presses_to_match_joltages(&machines).unwrap(),
+            33
+        )
+    }
+
+    #[test]
+    fn test_part2_matches_bfs_oracles() {
+        let mut state: u64 = 0x853c_49e6_748f_ea9b;
+        let mut next = |n: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % n) as usize
+        };
+        let mut input = String::new();
+        for _ in 0..40 {
+            // random buttons pressed a random number of times, so every machine is solvable
+            let n = 2 + next(4);
+            let buttons: Vec<Vec<usize>> = (0..2 + next(5))
+                .map(|_| (0..n).filter(|_| next(2) == 1).collect())
+                .filter(|b: &Vec<usize>| !b.is_empty())
+                .collect();
+            let mut joltages = vec![0; n];
+            for button in &buttons {
+                let presses = next(4);
+                button.iter().for_each(|&i| joltages[i] += presses);
+            }
+            let wiring: Vec<String> =
+                buttons.iter().map(|b| format!("({})", join(b))).collect();
+            input += &format!(
+                "[{}] {} {{{}}}\n",
+                ".".repeat(n),
+                wiring.join(" "),
+                join(&joltages)
+            );
+        }
+
+        for machine in parse_input(&input).unwrap() {
+            let plan = machine.min_button_presses_match_joltages().unwrap();
+            plan.check_joltages(&machine).unwrap();
+            let presses = plan.total() as usize;
+            assert_eq!(
+                presses,
+                machine.min_button_presses_match_joltages_naive_bfs().unwrap()
+            );
+            assert_eq!(
+                presses,
+                machine
+                    .min_button_presses_match_joltages_packed_bfs()
+                    .unwrap()
+            );
+        }
+    }
+
+    fn join(values: &[usize]) -> String {
+        values.iter().map(usize::to_string).collect::<Vec<_>>().join(",")
+    }
+
+    #[test]
+    fn test_part2_beyond_packing() {
+        // 10 counters of 9 bits each don't fit in a u64
+        let input = "
+[..........] (0,1,2,3,4) (5,6,7,8,9) (0,5) (1,6) (2,7) (3,8) (4,9) {300,300,300,300,300,400,400,400,400,400}
+            ";
+        let machines = parse_input(input).unwrap();
+        assert!(
+            machines[0]
+                .min_button_presses_match_joltages_packed_bfs()
+                .is_err()
+        );
+        // 300 of the first button, 400 of the second
+        assert_eq!(
+            compute_min_presses_to_match_joltages(&machines).unwrap(),
+            700
+        );
+    }
+
+    #[test]
+    fn test_part1_optimal_button_sets() {
+        let input = "
+[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
+            ";
+        let machines = parse_input(input).unwrap();
+        // press either (1,3) and (2,3), or (0,2) and (0,1)
+        let mut sets = machines[0].optimal_button_sets().unwrap();
+        sets.sort();
+        assert_eq!(sets, vec![vec![1, 3], vec![4, 5]]);
+        assert_eq!(
+            machines[1].optimal_button_sets().unwrap(),
+            vec![vec![2, 3, 4]]
+        );
+        assert_eq!(
+            machines[2].optimal_button_sets().unwrap(),
+            vec![vec![1, 2]]
+        );
+        for machine in &machines {
+            let plan = machine.min_button_presses_match_target().unwrap();
+            plan.check_lights(machine).unwrap();
+            assert_eq!(
+                plan.total() as usize,
+                machine.min_button_presses_match_target_bfs().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_part1_more_than_16_lights() {
+        let input = "
+[#.................#.] (0,18) (0,1) (1,18) (19) {1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1}
+            ";
+        let machines = parse_input(input).unwrap();
+        assert_eq!(compute_min_button_presses(&machines).unwrap(), 1);
+        assert_eq!(machines[0].optimal_button_sets().unwrap(), vec![vec![0]]);
+    }
+
+    #[test]
+    fn test_part1_free_button_limit() {
+        let input = format!("[#] {} {{40}}", vec!["(0)"; 40].join(" "));
+        let machines = parse_input(&input).unwrap();
+        let err = compute_min_button_presses(&machines).unwrap_err();
+        assert!(
+            format!("{:#}", err).ends_with(
+                "39 free variables, too many to try all 2^39 solutions (at most 32)"
+            ),
+            "{:#}",
+            err
+        );
+    }
+}
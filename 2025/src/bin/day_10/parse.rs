@@ -0,0 +1,292 @@
+//! Parser for machine lines such as `[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}`.
+//!
+//! Any amount of whitespace is accepted between tokens, including inside the parentheses and
+//! braces, but not inside numbers or the light diagram.
+
+use crate::{Machine, gf2::BitVec};
+use std::{fmt, ops::Range, str::FromStr};
+
+/// What the parser expected at some point of a line, and what it found there instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte range of the offending text in the line, empty at the end of the line
+    pub span: Range<usize>,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "at {}..{}: expected {}, found {}",
+            self.span.start, self.span.end, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Value with the byte range of the line it was parsed from.
+type Spanned<T> = (T, Range<usize>);
+
+/// Position in the line being parsed.
+struct Cursor<'a> {
+    line: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<char> {
+        self.line[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        self.pos += self.peek().map_or(0, char::len_utf8);
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    /// Error for the text in `span`, or for the next character if the span is empty.
+    fn error(
+        &self,
+        span: Range<usize>,
+        expected: impl Into<String>,
+    ) -> ParseError {
+        let (span, found) = if !span.is_empty() {
+            let found = format!("'{}'", &self.line[span.clone()]);
+            (span, found)
+        } else {
+            match self.line[span.start..].chars().next() {
+                Some(ch) => (
+                    span.start..span.start + ch.len_utf8(),
+                    format!("'{}'", ch),
+                ),
+                None => (span, "end of line".to_string()),
+            }
+        };
+        ParseError { span, expected: expected.into(), found }
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(), ParseError> {
+        if self.peek() != Some(ch) {
+            return Err(self.error(self.pos..self.pos, format!("'{}'", ch)));
+        }
+        self.bump();
+        Ok(())
+    }
+
+    /// Decimal number, with the byte range it came from.
+    fn number<T: FromStr>(
+        &mut self,
+        expected: &str,
+    ) -> Result<Spanned<T>, ParseError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+            self.bump();
+        }
+        let span = start..self.pos;
+        match self.line[span.clone()].parse() {
+            Ok(value) => Ok((value, span)),
+            Err(_) => Err(self.error(span, expected)),
+        }
+    }
+
+    /// Non-empty, comma separated list of numbers between `open` and `close`, with the byte
+    /// range of the whole list.
+    fn list<T: FromStr>(
+        &mut self,
+        open: char,
+        close: char,
+        expected: &str,
+    ) -> Result<Spanned<Vec<Spanned<T>>>, ParseError> {
+        let start = self.pos;
+        self.expect(open)?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            items.push(self.number(expected)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.bump(),
+                Some(ch) if ch == close => break,
+                _ => {
+                    return Err(self.error(
+                        self.pos..self.pos,
+                        format!("',' or '{}'", close),
+                    ));
+                }
+            }
+        }
+        self.bump();
+        Ok((items, start..self.pos))
+    }
+
+    /// Light diagram such as `[.##.]`.
+    fn diagram(&mut self) -> Result<BitVec, ParseError> {
+        self.expect('[')?;
+        let mut lights = Vec::new();
+        loop {
+            match self.peek() {
+                Some('.') => lights.push(false),
+                Some('#') => lights.push(true),
+                Some(']') => break,
+                _ => {
+                    return Err(
+                        self.error(self.pos..self.pos, "'.', '#' or ']'")
+                    );
+                }
+            }
+            self.bump();
+        }
+        self.bump();
+        let mut target = BitVec::zeros(lights.len());
+        for (idx, on) in lights.into_iter().enumerate() {
+            target.set(idx, on);
+        }
+        Ok(target)
+    }
+
+    /// Wiring schematic such as `(0,2)` for a machine with `n` lights.
+    fn wiring(&mut self, n: usize) -> Result<BitVec, ParseError> {
+        let (indices, _) = self.list::<usize>('(', ')', "light index")?;
+        let mut toggle = BitVec::zeros(n);
+        for (idx, span) in indices {
+            if idx >= n {
+                return Err(
+                    self.error(span, format!("light index below {}", n))
+                );
+            }
+            if toggle.get(idx) {
+                return Err(self.error(span, "each light at most once"));
+            }
+            toggle.set(idx, true);
+        }
+        Ok(toggle)
+    }
+
+    /// Joltage requirements such as `{3,5,4,7}`, one per light.
+    fn joltages(&mut self, n: usize) -> Result<Vec<u16>, ParseError> {
+        let (joltages, span) =
+            self.list::<u16>('{', '}', "joltage from 0 to 65535")?;
+        if joltages.len() != n {
+            return Err(ParseError {
+                span,
+                expected: format!("{} joltages, one per light", n),
+                found: format!("{}", joltages.len()),
+            });
+        }
+        Ok(joltages.into_iter().map(|(joltage, _)| joltage).collect())
+    }
+}
+
+impl FromStr for Machine {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor { line, pos: 0 };
+        cursor.skip_whitespace();
+        let target = cursor.diagram()?;
+        let n = target.len();
+
+        let mut toggles = Vec::new();
+        let joltages = loop {
+            cursor.skip_whitespace();
+            match cursor.peek() {
+                Some('(') => toggles.push(cursor.wiring(n)?),
+                Some('{') => break cursor.joltages(n)?,
+                _ => {
+                    return Err(
+                        cursor.error(cursor.pos..cursor.pos, "'(' or '{'")
+                    );
+                }
+            }
+        };
+
+        cursor.skip_whitespace();
+        if cursor.pos < line.len() {
+            return Err(cursor.error(cursor.pos..line.len(), "end of line"));
+        }
+        Ok(Machine { target, toggles, joltages })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn error(line: &str) -> ParseError {
+        line.parse::<Machine>().unwrap_err()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for line in [
+            "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}",
+            "[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}",
+            "[#] {0}",
+        ] {
+            let machine: Machine = line.parse().unwrap();
+            assert_eq!(machine.to_string(), line);
+        }
+
+        let spaced =
+            "  [.##.](3) ( 1 , 3 )\t(2)(2,3) (0, 2) (0,1){ 3,5 ,4,7 } ";
+        let machine: Machine = spaced.parse().unwrap();
+        assert_eq!(
+            machine.to_string(),
+            "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let e = error("[.##.] (3) (1,4) {3,5,4,7}");
+        assert_eq!(e.span, 14..15);
+        assert_eq!(e.expected, "light index below 4");
+        assert_eq!(e.found, "'4'");
+
+        let e = error("[.##.] (3) {3,5,4}");
+        assert_eq!(e.span, 11..18);
+        assert_eq!(e.expected, "4 joltages, one per light");
+        assert_eq!(e.found, "3");
+
+        let e = error("[.x#.] (3) {3,5,4,7}");
+        assert_eq!((e.span, e.found), (2..3, "'x'".to_string()));
+
+        let e = error("[.##. (3) {3,5,4,7}");
+        assert_eq!(e.expected, "'.', '#' or ']'");
+        assert_eq!(e.found, "' '");
+
+        let e = error("[.##.] (3,) {3,5,4,7}");
+        assert_eq!((e.span, e.expected), (10..11, "light index".to_string()));
+
+        let e = error("[.##.] (3) {3,5,4,70000}");
+        assert_eq!(e.span, 18..23);
+        assert_eq!(e.found, "'70000'");
+
+        let e = error("[.##.] (3) (1 3) {3,5,4,7}");
+        assert_eq!((e.span, e.expected), (14..15, "',' or ')'".to_string()));
+
+        let e = error("[.##.] (3) (1,1) {3,5,4,7}");
+        assert_eq!(e.expected, "each light at most once");
+
+        let e = error("[.##.] (3) [.] {3,5,4,7}");
+        assert_eq!((e.span, e.expected), (11..12, "'(' or '{'".to_string()));
+
+        let e = error("[.##.] (3) {3,5,4,7} extra");
+        assert_eq!(e.span, 21..26);
+        assert_eq!(e.found, "'extra'");
+
+        let e = error("[.##.] (3)");
+        assert_eq!(e.span, 10..10);
+        assert_eq!(
+            e.to_string(),
+            "at 10..10: expected '(' or '{', found end of line"
+        );
+    }
+}
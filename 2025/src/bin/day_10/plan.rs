@@ -0,0 +1,167 @@
+//! Which buttons to press on a machine, and how often.
+
+use crate::{
+    Machine, format_diagram, format_joltages, format_wiring, gf2::BitVec,
+};
+use std::fmt;
+
+/// Number of presses of every button, in wiring order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PressPlan {
+    pub presses: Vec<u64>,
+}
+
+impl PressPlan {
+    /// Total number of button presses.
+    pub fn total(&self) -> u64 {
+        self.presses.iter().sum()
+    }
+
+    /// Button indices in the order they get pressed: each button as often as planned.
+    fn sequence(&self) -> impl Iterator<Item = usize> + '_ {
+        self.presses
+            .iter()
+            .enumerate()
+            .flat_map(|(button, &count)| (0..count).map(move |_| button))
+    }
+
+    /// Lights and joltage counters after pressing the plan's buttons on `machine`, starting
+    /// with every light off and every counter at 0.
+    fn replay(&self, machine: &Machine) -> anyhow::Result<(BitVec, Vec<u64>)> {
+        if self.presses.len() != machine.toggles.len() {
+            anyhow::bail!(
+                "plan has {} buttons, machine has {}",
+                self.presses.len(),
+                machine.toggles.len()
+            );
+        }
+        let mut lights = BitVec::zeros(machine.target.len());
+        let mut counters = vec![0; machine.joltages.len()];
+        for (toggle, &count) in machine.toggles.iter().zip(&self.presses) {
+            if count % 2 == 1 {
+                lights ^= toggle;
+            }
+            toggle.ones().for_each(|i| counters[i] += count);
+        }
+        Ok((lights, counters))
+    }
+
+    /// Checks that the plan leaves `machine` showing its target light diagram.
+    pub fn check_lights(&self, machine: &Machine) -> anyhow::Result<()> {
+        let (lights, _) = self.replay(machine)?;
+        if lights != machine.target {
+            anyhow::bail!(
+                "plan ends with lights {} instead of {}",
+                format_diagram(&lights),
+                format_diagram(&machine.target)
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that the plan leaves `machine` at exactly its joltage requirements.
+    pub fn check_joltages(&self, machine: &Machine) -> anyhow::Result<()> {
+        let (_, counters) = self.replay(machine)?;
+        if counters
+            .iter()
+            .copied()
+            .ne(machine.joltages.iter().map(|&j| u64::from(j)))
+        {
+            anyhow::bail!(
+                "plan ends with joltages {} instead of {}",
+                format_joltages(&counters),
+                format_joltages(&machine.joltages)
+            );
+        }
+        Ok(())
+    }
+
+    /// Step by step display of the plan on `machine`.
+    pub fn animate<'a>(&'a self, machine: &'a Machine) -> Animation<'a> {
+        Animation { plan: self, machine }
+    }
+}
+
+/// The machine followed by one line per button press, showing the lights and joltage counters
+/// after it.
+pub struct Animation<'a> {
+    plan: &'a PressPlan,
+    machine: &'a Machine,
+}
+
+impl fmt::Display for Animation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let machine = self.machine;
+        writeln!(f, "{}", machine)?;
+
+        let labels: Vec<String> = self
+            .plan
+            .sequence()
+            .map(|button| {
+                format!("press {}", format_wiring(&machine.toggles[button]))
+            })
+            .collect();
+        let width = labels.iter().map(String::len).max().unwrap_or(0);
+
+        let mut lights = BitVec::zeros(machine.target.len());
+        let mut counters = vec![0u64; machine.joltages.len()];
+        writeln!(
+            f,
+            "{:>width$}: {} {}",
+            "start",
+            format_diagram(&lights),
+            format_joltages(&counters)
+        )?;
+        for (button, label) in self.plan.sequence().zip(&labels) {
+            let toggle = &machine.toggles[button];
+            lights ^= toggle;
+            toggle.ones().for_each(|i| counters[i] += 1);
+            writeln!(
+                f,
+                "{:>width$}: {} {}",
+                label,
+                format_diagram(&lights),
+                format_joltages(&counters)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_animate_and_check() {
+        let machine: Machine =
+            "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}"
+                .parse()
+                .unwrap();
+        let plan = PressPlan { presses: vec![0, 0, 0, 0, 1, 1] };
+        plan.check_lights(&machine).unwrap();
+        let animation = plan.animate(&machine).to_string();
+        let frames: Vec<&str> = animation.lines().rev().take(3).collect();
+        assert_eq!(
+            frames,
+            vec![
+                "press (0,1): [.##.] {2,1,1,0}",
+                "press (0,2): [#.#.] {1,0,1,0}",
+                "      start: [....] {0,0,0,0}",
+            ]
+        );
+
+        let err = plan.check_joltages(&machine).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plan ends with joltages {2,1,1,0} instead of {3,5,4,7}"
+        );
+        let short = PressPlan { presses: vec![1] };
+        assert!(short.check_lights(&machine).is_err());
+        let wrong = PressPlan { presses: vec![1, 0, 0, 0, 0, 0] };
+        assert_eq!(
+            wrong.check_lights(&machine).unwrap_err().to_string(),
+            "plan ends with lights [...#] instead of [.##.]"
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Graphviz DOT drawing of the device graph, labelled with the path counts of one query.
+//!
+//! See: <https://graphviz.org/doc/info/lang.html>
+
+use crate::graph::{Graph, PathCounts, PathQuery};
+use std::fmt::Write;
+
+/// Draws every device with its number of paths from the source and on to the target.
+///
+/// The source, target and required devices are filled in. Devices on no source→target path and
+/// edges on no path through all the required devices are greyed out.
+pub fn render(
+    graph: &Graph,
+    query: &PathQuery,
+    counts: &PathCounts,
+) -> String {
+    let show = |count: Option<u128>| {
+        count.map_or_else(|| "overflow".to_string(), |c| c.to_string())
+    };
+
+    let mut dot = String::new();
+    writeln!(dot, "digraph devices {{").unwrap();
+    writeln!(
+        dot,
+        "  node [shape=box, style=\"rounded,filled\", fillcolor=white];"
+    )
+    .unwrap();
+    for id in graph.ids() {
+        let name = graph.name(id);
+        let fill = if name == query.source {
+            "palegreen"
+        } else if name == query.target {
+            "lightpink"
+        } else if query.required.contains(&name) {
+            "gold"
+        } else {
+            "white"
+        };
+        if counts.is_kept(id) {
+            writeln!(
+                dot,
+                "  {:?} [label=\"{}\\nfrom {}: {}\\nto {}: {}\", fillcolor={}];",
+                name,
+                name,
+                query.source,
+                show(counts.paths_from_source(id)),
+                query.target,
+                show(counts.paths_to_target(id)),
+                fill
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                dot,
+                "  {:?} [fillcolor={}, color=grey, fontcolor=grey];",
+                name, fill
+            )
+            .unwrap();
+        }
+    }
+    for from in graph.ids() {
+        for &to in graph.outputs(from) {
+            let style = if counts.is_valid_edge(from, to) {
+                ""
+            } else {
+                " [color=grey80]"
+            };
+            writeln!(
+                dot,
+                "  {:?} -> {:?}{};",
+                graph.name(from),
+                graph.name(to),
+                style
+            )
+            .unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_counts_and_greyed_edges() {
+        let input = "
+svr: aaa bbb zzz
+aaa: fft
+bbb: fft out
+fft: dac
+dac: out
+zzz: yyy";
+        let graph = Graph::parse(input).unwrap();
+        let query = PathQuery::new("svr", "out").through(&["dac", "fft"]);
+        let counts = graph.path_counts(&query).unwrap();
+        assert_eq!(counts.total(), 2);
+        let dot = render(&graph, &query, &counts);
+
+        assert!(dot.contains(
+            r#""svr" [label="svr\nfrom svr: 1\nto out: 3", fillcolor=palegreen];"#
+        ));
+        assert!(dot.contains(
+            r#""fft" [label="fft\nfrom svr: 2\nto out: 1", fillcolor=gold];"#
+        ));
+        assert!(dot.contains(r#""out" [label="out\nfrom svr: 3\nto out: 1""#));
+        // zzz never reaches out, and bbb -> out skips both required devices
+        assert!(dot.contains(
+            r#""zzz" [fillcolor=white, color=grey, fontcolor=grey];"#
+        ));
+        assert!(dot.contains(r#""svr" -> "zzz" [color=grey80];"#));
+        assert!(dot.contains(r#""bbb" -> "out" [color=grey80];"#));
+        assert!(dot.contains("  \"bbb\" -> \"fft\";\n"));
+    }
+}
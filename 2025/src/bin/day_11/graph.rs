@@ -0,0 +1,499 @@
+//! Directed graph of devices and the outputs they feed, with path counting.
+//!
+//! Device names are interned into dense [`NodeId`]s and the edges stored in compressed sparse
+//! row form, both ways round. Every traversal is iterative, so deep graphs can't overflow the
+//! stack.
+//!
+//! Counting walks the nodes in topological order, which only exists when no path can loop back
+//! on itself, so cycles are reported instead.
+
+use std::collections::HashMap;
+
+/// Dense index of a device, in order of first appearance in the input.
+pub type NodeId = u32;
+
+/// Compressed sparse row adjacency: the neighbours of node `v` are
+/// `targets[offsets[v]..offsets[v + 1]]`.
+#[derive(Debug)]
+struct Csr {
+    offsets: Vec<usize>,
+    targets: Vec<NodeId>,
+}
+
+impl Csr {
+    /// Adjacency of `n` nodes from `(from, to)` edges, keeping the edge order per node.
+    fn new(
+        n: usize,
+        edges: impl Iterator<Item = (NodeId, NodeId)> + Clone,
+    ) -> Self {
+        let mut offsets = vec![0; n + 1];
+        for (from, _) in edges.clone() {
+            offsets[from as usize + 1] += 1;
+        }
+        for v in 0..n {
+            offsets[v + 1] += offsets[v];
+        }
+        let mut next = offsets.clone();
+        let mut targets = vec![0; offsets[n]];
+        for (from, to) in edges {
+            targets[next[from as usize]] = to;
+            next[from as usize] += 1;
+        }
+        Self { offsets, targets }
+    }
+
+    fn neighbours(&self, v: NodeId) -> &[NodeId] {
+        let v = v as usize;
+        &self.targets[self.offsets[v]..self.offsets[v + 1]]
+    }
+}
+
+/// Device graph as parsed from `device: output output ...` lines.
+#[derive(Debug)]
+pub struct Graph<'a> {
+    /// Name of every node, indexed by id
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, NodeId>,
+    outputs: Csr,
+    inputs: Csr,
+}
+
+/// Which source→target paths to count.
+#[derive(Debug, Clone)]
+pub struct PathQuery<'a> {
+    pub source: &'a str,
+    pub target: &'a str,
+    /// Nodes every path must pass through, in any order
+    pub required: Vec<&'a str>,
+    /// Nodes no path may pass through
+    pub forbidden: Vec<&'a str>,
+}
+
+impl<'a> PathQuery<'a> {
+    /// Every path from `source` to `target`.
+    pub fn new(source: &'a str, target: &'a str) -> Self {
+        Self { source, target, required: Vec::new(), forbidden: Vec::new() }
+    }
+
+    /// Only paths visiting all of `nodes`.
+    pub fn through(mut self, nodes: &[&'a str]) -> Self {
+        self.required.extend_from_slice(nodes);
+        self
+    }
+
+    /// Only paths visiting none of `nodes`.
+    pub fn avoiding(mut self, nodes: &[&'a str]) -> Self {
+        self.forbidden.extend_from_slice(nodes);
+        self
+    }
+}
+
+impl<'a> Graph<'a> {
+    pub fn parse(contents: &'a str) -> anyhow::Result<Self> {
+        let mut names = Vec::new();
+        let mut ids = HashMap::new();
+        let mut intern = |name: &'a str| -> NodeId {
+            *ids.entry(name).or_insert_with(|| {
+                names.push(name);
+                (names.len() - 1) as NodeId
+            })
+        };
+        let mut edges = Vec::new();
+        for line in contents.trim().lines() {
+            let Some((device, rest)) = line.split_once(':') else {
+                anyhow::bail!("Invalid input line: {}", line)
+            };
+            let from = intern(device.trim());
+            for output in rest.split_whitespace() {
+                edges.push((from, intern(output)));
+            }
+        }
+        let n = names.len();
+        let outputs = Csr::new(n, edges.iter().copied());
+        let inputs = Csr::new(n, edges.iter().map(|&(from, to)| (to, from)));
+        Ok(Self { names, ids, outputs, inputs })
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &'a str {
+        self.names[id as usize]
+    }
+
+    /// Devices fed by `id`.
+    pub fn outputs(&self, id: NodeId) -> &[NodeId] {
+        self.outputs.neighbours(id)
+    }
+
+    /// Every node reachable from `start` along `adjacency`, without entering `blocked` nodes or
+    /// leaving `stop`.
+    fn reachable(
+        &self,
+        adjacency: &Csr,
+        start: NodeId,
+        blocked: &[bool],
+        stop: NodeId,
+    ) -> Vec<bool> {
+        let mut seen = vec![false; self.names.len()];
+        if blocked[start as usize] {
+            return seen;
+        }
+        seen[start as usize] = true;
+        let mut stack = vec![start];
+        while let Some(v) = stack.pop() {
+            if v == stop {
+                continue;
+            }
+            for &w in adjacency.neighbours(v) {
+                if !seen[w as usize] && !blocked[w as usize] {
+                    seen[w as usize] = true;
+                    stack.push(w);
+                }
+            }
+        }
+        seen
+    }
+
+    /// The `keep` nodes in topological order of the edges between them, ignoring the out edges
+    /// of `stop`.
+    ///
+    /// # Errors
+    ///
+    /// If the kept nodes contain a cycle, naming the nodes around it.
+    fn topological_order(
+        &self,
+        keep: &[bool],
+        stop: NodeId,
+    ) -> anyhow::Result<Vec<NodeId>> {
+        let kept_inputs = |w: NodeId| {
+            self.inputs
+                .neighbours(w)
+                .iter()
+                .copied()
+                .filter(move |&v| keep[v as usize] && v != stop)
+        };
+        // Kahn's algorithm
+        let mut in_degree: Vec<usize> = (0..self.names.len() as NodeId)
+            .map(|w| kept_inputs(w).count())
+            .collect();
+        let mut ready: Vec<NodeId> = (0..self.names.len() as NodeId)
+            .filter(|&v| keep[v as usize] && in_degree[v as usize] == 0)
+            .collect();
+        let mut order = Vec::new();
+        while let Some(v) = ready.pop() {
+            order.push(v);
+            if v == stop {
+                continue;
+            }
+            for &w in self.outputs(v) {
+                if keep[w as usize] {
+                    in_degree[w as usize] -= 1;
+                    if in_degree[w as usize] == 0 {
+                        ready.push(w);
+                    }
+                }
+            }
+        }
+        if order.len() == keep.iter().filter(|&&k| k).count() {
+            return Ok(order);
+        }
+
+        // every leftover node has a leftover input, so walking inputs back must repeat a node
+        let leftover =
+            |v: NodeId| keep[v as usize] && in_degree[v as usize] > 0;
+        let mut step = vec![None; self.names.len()];
+        let mut v = (0..self.names.len() as NodeId)
+            .find(|&v| leftover(v))
+            .expect("leftover node");
+        while step[v as usize].is_none() {
+            let u =
+                kept_inputs(v).find(|&u| leftover(u)).expect("leftover input");
+            step[v as usize] = Some(u);
+            v = u;
+        }
+        // walking back from v gives the cycle in reverse
+        let mut cycle = vec![self.name(v)];
+        let mut u = step[v as usize].expect("on cycle");
+        while u != v {
+            cycle.push(self.name(u));
+            u = step[u as usize].expect("on cycle");
+        }
+        cycle.push(self.name(v));
+        cycle.reverse();
+        anyhow::bail!("cycle in device graph: {}", cycle.join(" -> "))
+    }
+
+    /// Number of paths matching `query`. A path ends the first time it reaches the target.
+    ///
+    /// # Errors
+    ///
+    /// As for [`Graph::path_counts`].
+    pub fn count_paths(&self, query: &PathQuery<'a>) -> anyhow::Result<u128> {
+        Ok(self.path_counts(query)?.total())
+    }
+
+    /// Paths matching `query` into and out of every node.
+    ///
+    /// Only nodes on some source→target path are kept, so cycles elsewhere don't matter. Counts
+    /// are pushed forward from the source and back from the target in topological order, per
+    /// node and set of required nodes seen as a bitmask.
+    ///
+    /// # Errors
+    ///
+    /// If the source or target is not in the graph, more than 64 nodes are required, a cycle
+    /// lies on a source→target path, or a count overflows a `u128`.
+    pub fn path_counts(
+        &self,
+        query: &PathQuery<'a>,
+    ) -> anyhow::Result<PathCounts> {
+        let Some(source) = self.id(query.source) else {
+            anyhow::bail!("unreachable: source node is not in the graph")
+        };
+        let Some(target) = self.id(query.target) else {
+            anyhow::bail!("unreachable: target node is not in the graph")
+        };
+        let k = query.required.len();
+        if k > 64 {
+            anyhow::bail!("at most 64 required nodes are supported, got {}", k)
+        }
+        // a required node that isn't in the graph leaves its bit unset everywhere, so no path
+        // can ever see all of them
+        let all = if k == 64 { u64::MAX } else { (1 << k) - 1 };
+        let mut bits = vec![0u64; self.names.len()];
+        for (i, name) in query.required.iter().enumerate() {
+            if let Some(id) = self.id(name) {
+                bits[id as usize] |= 1 << i;
+            }
+        }
+        let mut blocked = vec![false; self.names.len()];
+        for id in query.forbidden.iter().filter_map(|name| self.id(name)) {
+            blocked[id as usize] = true;
+        }
+
+        let from_source =
+            self.reachable(&self.outputs, source, &blocked, target);
+        let to_target = self.reachable(&self.inputs, target, &blocked, source);
+        let keep: Vec<bool> = from_source
+            .iter()
+            .zip(&to_target)
+            .map(|(a, b)| *a && *b)
+            .collect();
+        let order = self.topological_order(&keep, target)?;
+
+        let add = |paths: &mut HashMap<u64, u128>, seen: u64, count: u128| {
+            let entry = paths.entry(seen).or_default();
+            *entry = entry
+                .checked_add(count)
+                .ok_or_else(|| anyhow::anyhow!("path count overflows u128"))?;
+            anyhow::Ok(())
+        };
+        let mut counts = PathCounts {
+            source,
+            keep,
+            all,
+            from_source: vec![HashMap::new(); self.names.len()],
+            to_target: vec![HashMap::new(); self.names.len()],
+        };
+        let kept_outputs = |v: NodeId| {
+            self.outputs(v)
+                .iter()
+                .copied()
+                .filter(|&w| counts.keep[w as usize])
+                .collect::<Vec<_>>()
+        };
+        let mut edges: Vec<(NodeId, Vec<NodeId>)> = order
+            .iter()
+            .filter(|&&v| v != target)
+            .map(|&v| (v, kept_outputs(v)))
+            .collect();
+
+        if counts.keep[source as usize] {
+            counts.from_source[source as usize]
+                .insert(bits[source as usize], 1);
+            counts.to_target[target as usize].insert(bits[target as usize], 1);
+        }
+        for (v, outputs) in &edges {
+            let here = counts.from_source[*v as usize].clone();
+            for &w in outputs {
+                for (&seen, &count) in &here {
+                    let seen = seen | bits[w as usize];
+                    add(&mut counts.from_source[w as usize], seen, count)?;
+                }
+            }
+        }
+        edges.reverse();
+        for (v, outputs) in &edges {
+            for &w in outputs {
+                let there = counts.to_target[w as usize].clone();
+                for (seen, count) in there {
+                    let seen = seen | bits[*v as usize];
+                    add(&mut counts.to_target[*v as usize], seen, count)?;
+                }
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Every node id, in order of first appearance.
+    pub fn ids(&self) -> impl Iterator<Item = NodeId> + use<> {
+        0..self.names.len() as NodeId
+    }
+}
+
+/// Paths of one [`PathQuery`] into and out of every node.
+#[derive(Debug)]
+pub struct PathCounts {
+    source: NodeId,
+    /// Whether each node is on some source→target path, ignoring the required nodes
+    keep: Vec<bool>,
+    /// Bitmask of all the required nodes
+    all: u64,
+    /// Paths from the source into each node, by the required nodes seen on the way
+    from_source: Vec<HashMap<u64, u128>>,
+    /// Paths from each node on to the target, by the required nodes seen on the way
+    to_target: Vec<HashMap<u64, u128>>,
+}
+
+impl PathCounts {
+    /// Paths from the source to the target through all the required nodes.
+    pub fn total(&self) -> u128 {
+        self.to_target[self.source as usize]
+            .get(&self.all)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Whether `id` is on some source→target path, whichever nodes it passes.
+    pub fn is_kept(&self, id: NodeId) -> bool {
+        self.keep[id as usize]
+    }
+
+    /// Paths from the source to `id` that can go on to the target, or `None` if the total
+    /// overflows.
+    pub fn paths_from_source(&self, id: NodeId) -> Option<u128> {
+        sum(&self.from_source[id as usize])
+    }
+
+    /// Paths from `id` to the target, or `None` if the total overflows.
+    pub fn paths_to_target(&self, id: NodeId) -> Option<u128> {
+        sum(&self.to_target[id as usize])
+    }
+
+    /// Whether the edge `from -> to` lies on some path through all the required nodes.
+    pub fn is_valid_edge(&self, from: NodeId, to: NodeId) -> bool {
+        if !(self.keep[from as usize] && self.keep[to as usize]) {
+            return false;
+        }
+        let after = &self.to_target[to as usize];
+        self.from_source[from as usize]
+            .keys()
+            .any(|&seen| after.keys().any(|&rest| seen | rest == self.all))
+    }
+}
+
+fn sum(paths: &HashMap<u64, u128>) -> Option<u128> {
+    paths.values().try_fold(0u128, |acc, &count| acc.checked_add(count))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "
+svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out";
+
+    #[test]
+    fn test_required_and_forbidden() {
+        let graph = Graph::parse(EXAMPLE).unwrap();
+        let count = |query: PathQuery| graph.count_paths(&query).unwrap();
+        assert_eq!(count(PathQuery::new("svr", "out")), 8);
+        assert_eq!(count(PathQuery::new("svr", "out").through(&["fft"])), 4);
+        assert_eq!(
+            count(PathQuery::new("svr", "out").through(&["dac", "fft"])),
+            2
+        );
+        assert_eq!(
+            count(
+                PathQuery::new("svr", "out")
+                    .through(&["dac"])
+                    .avoiding(&["bbb", "ggg"])
+            ),
+            1
+        );
+        // a required node that isn't in the graph can never be visited
+        assert_eq!(count(PathQuery::new("svr", "out").through(&["xyz"])), 0);
+        assert_eq!(count(PathQuery::new("svr", "out").avoiding(&["out"])), 0);
+    }
+
+    #[test]
+    fn test_cycle_reported() {
+        let graph = Graph::parse("a: b\nb: c out\nc: d\nd: b").unwrap();
+        let err = graph.count_paths(&PathQuery::new("a", "out")).unwrap_err();
+        assert_eq!(err.to_string(), "cycle in device graph: b -> c -> d -> b");
+        // cutting the loop makes the graph a DAG again
+        let query = PathQuery::new("a", "out").avoiding(&["d"]);
+        assert_eq!(graph.count_paths(&query).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_count_beyond_u64() {
+        // 100 diamonds in a row: 2^100 paths
+        let lines: Vec<String> = (0..100)
+            .map(|i| {
+                format!("n{i}: l{i} r{i}\nl{i}: n{}\nr{i}: n{}", i + 1, i + 1)
+            })
+            .collect();
+        let input = lines.join("\n");
+        let graph = Graph::parse(&input).unwrap();
+        let query = PathQuery::new("n0", "n100");
+        assert_eq!(graph.count_paths(&query).unwrap(), 1 << 100);
+    }
+
+    #[test]
+    fn test_interned_adjacency() {
+        let graph = Graph::parse("a: b out\nb: out\na: c").unwrap();
+        let names = |id| -> Vec<&str> {
+            graph.outputs(id).iter().map(|&w| graph.name(w)).collect()
+        };
+        // outputs only devices get an id too, and repeated lines add up
+        let out = graph.id("out").unwrap();
+        assert_eq!(graph.name(out), "out");
+        assert!(graph.outputs(out).is_empty());
+        assert_eq!(names(graph.id("a").unwrap()), vec!["b", "out", "c"]);
+        assert_eq!(graph.id("d"), None);
+    }
+
+    #[test]
+    fn test_cycle_off_path_ignored() {
+        // x and y loop forever but never reach the target
+        let graph = Graph::parse("a: b x\nb: out\nx: y\ny: x").unwrap();
+        assert_eq!(graph.count_paths(&PathQuery::new("a", "out")).unwrap(), 1);
+        // past the target doesn't count either
+        let graph = Graph::parse("a: out\nout: a").unwrap();
+        assert_eq!(graph.count_paths(&PathQuery::new("a", "out")).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_deep_chain() {
+        let lines: Vec<String> =
+            (0..200_000).map(|i| format!("n{}: n{}", i, i + 1)).collect();
+        let input = lines.join("\n");
+        let graph = Graph::parse(&input).unwrap();
+        let query = PathQuery::new("n0", "n200000").through(&["n123456"]);
+        assert_eq!(graph.count_paths(&query).unwrap(), 1);
+    }
+}
@@ -0,0 +1,153 @@
+use clap::Parser;
+use graph::{Graph, PathQuery};
+use std::collections::HashSet;
+
+mod dot;
+mod graph;
+
+/// First attempt: DFS over every path with a visited set, exponential in the number of paths
+#[allow(dead_code)]
+fn count_paths_visited_dfs(
+    graph: &Graph,
+    source: &str,
+    target: &str,
+) -> usize {
+    let (Some(source), Some(target)) = (graph.id(source), graph.id(target))
+    else {
+        return 0;
+    };
+    let mut count = 0;
+    let mut visited = HashSet::from([source]);
+    // (node, index of its next output to try) for every node on the current path
+    let mut stack = vec![(source, 0)];
+    while let Some((current, next)) = stack.last_mut() {
+        let current = *current;
+        if current == target {
+            count += 1;
+        } else if let Some(&nbr) = graph.outputs(current).get(*next) {
+            *next += 1;
+            if visited.insert(nbr) {
+                stack.push((nbr, 0));
+            }
+            continue;
+        }
+        visited.remove(&current);
+        stack.pop();
+    }
+    count
+}
+
+#[derive(Parser)]
+struct Args {
+    /// Devices every part 2 path must pass through.
+    #[arg(long, value_delimiter = ',', default_value = "dac,fft")]
+    through: Vec<String>,
+    /// Devices no path may pass through, in either part.
+    #[arg(long, value_delimiter = ',')]
+    avoid: Vec<String>,
+    /// Write the device graph in Graphviz DOT format to this path, labelled with the part 2
+    /// path counts.
+    #[arg(long)]
+    dot: Option<std::path::PathBuf>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let contents = std::fs::read_to_string("./data/day-11-input.txt")?;
+    let graph = Graph::parse(&contents)?;
+    let through: Vec<&str> = args.through.iter().map(String::as_str).collect();
+    let avoid: Vec<&str> = args.avoid.iter().map(String::as_str).collect();
+
+    /* Part 1 */
+    let query = PathQuery::new("you", "out").avoiding(&avoid);
+    println!("Part 1: {}", graph.count_paths(&query)?);
+
+    /* Part 2 */
+    let query =
+        PathQuery::new("svr", "out").through(&through).avoiding(&avoid);
+    let counts = graph.path_counts(&query)?;
+    println!("Part 2: {}", counts.total());
+
+    if let Some(path) = args.dot {
+        std::fs::write(path, dot::render(&graph, &query, &counts))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_part1_example() {
+        let input = "
+aaa: you hhh
+you: bbb ccc
+bbb: ddd eee
+ccc: ddd eee fff
+ddd: ggg
+eee: out
+fff: out
+ggg: out
+hhh: ccc fff iii
+iii: out";
+        let graph = Graph::parse(input).unwrap();
+        let query = PathQuery::new("you", "out");
+        assert_eq!(graph.count_paths(&query).unwrap(), 5);
+        assert_eq!(count_paths_visited_dfs(&graph, "you", "out"), 5);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        let input = "
+svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out";
+        let graph = Graph::parse(input).unwrap();
+        let query = PathQuery::new("svr", "out").through(&["dac", "fft"]);
+        assert_eq!(graph.count_paths(&query).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_matches_visited_dfs() {
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = |n: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % n) as usize
+        };
+        for _ in 0..30 {
+            // edges only go to higher numbers, so the graph is a DAG
+            let n = 3 + next(10);
+            let lines: Vec<String> = (0..n)
+                .map(|v| {
+                    let outputs: Vec<String> = (v + 1..n)
+                        .filter(|_| next(3) == 0)
+                        .map(|w| format!("d{}", w))
+                        .collect();
+                    format!("d{}: {}", v, outputs.join(" "))
+                })
+                .collect();
+            let input = lines.join("\n");
+            let graph = Graph::parse(&input).unwrap();
+            let target = format!("d{}", n - 1);
+            let query = PathQuery::new("d0", &target);
+            assert_eq!(
+                graph.count_paths(&query).unwrap(),
+                count_paths_visited_dfs(&graph, "d0", &target) as u128
+            );
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use anyhow::Context;
+use aoc_common::parallel;
+use clap::Parser;
+use packing::{Packing, Placed};
+use shape::Shape;
+use std::time::Duration;
+
+mod packing;
+mod placement;
+mod shape;
+
+#[derive(Debug)]
+struct Region {
+    /// The width of the region.
+    width: usize,
+    /// The length of the region.
+    length: usize,
+    /// The quantities of each shape, where quantities[i] = amount of the ith shape
+    quantities: Vec<usize>,
+}
+
+impl Region {
+    /// 2D version of the bin packing problem: where to put down every present, if they fit.
+    ///
+    /// See: <https://en.wikipedia.org/wiki/Bin_packing_problem>
+    fn pack(&self, shapes: &[Shape], budget: Option<Duration>) -> Packing {
+        packing::pack(
+            shapes,
+            self.width,
+            self.length,
+            &self.quantities,
+            budget,
+        )
+    }
+
+    /// See [`placement::check`].
+    fn check(
+        &self,
+        shapes: &[Shape],
+        placement: &[Placed],
+    ) -> anyhow::Result<()> {
+        placement::check(
+            shapes,
+            self.width,
+            self.length,
+            &self.quantities,
+            placement,
+        )
+    }
+}
+
+fn parse_input_spec(
+    contents: &str,
+) -> anyhow::Result<(Vec<Shape>, Vec<Region>)>
+where
+{
+    let mut lines = contents.trim().lines().peekable();
+    let mut shapes: Vec<Shape> = Vec::new();
+    let mut curr_idx = 0;
+
+    // parse shapes
+    while let Some(line) = lines.peek()
+        && !line.contains("x")
+    {
+        let line = lines.next().unwrap();
+        let (idx, rest) = line.split_once(":").with_context(|| {
+            format!("expected shape index line, got: {}", line)
+        })?;
+        assert_eq!(idx.parse::<usize>()?, curr_idx);
+        assert!(rest.is_empty());
+
+        // parse diagram
+        let mut diagram = String::new();
+        while let Some(row) = lines.next()
+            && !row.is_empty()
+        {
+            diagram.push_str(row);
+            diagram.push('\n');
+        }
+        shapes.push(
+            diagram
+                .parse()
+                .with_context(|| format!("invalid shape {}", curr_idx))?,
+        );
+
+        curr_idx += 1;
+    }
+
+    // parse regions
+    let mut regions = Vec::new();
+    for line in lines {
+        if let Some((dims, quantities)) = line.split_once(':') {
+            let (width, length) = dims
+                .split_once('x')
+                .with_context(|| format!("no 'x' in dims: {}", dims))?;
+            let (width, length): (usize, usize) =
+                (width.parse()?, length.parse()?);
+
+            let quantities = quantities
+                .split_whitespace()
+                .map(|q| {
+                    q.parse::<usize>()
+                        .with_context(|| format!("invalid quantity: {}", q))
+                })
+                .collect::<anyhow::Result<Vec<usize>>>()?;
+
+            assert_eq!(quantities.len(), shapes.len());
+            regions.push(Region { length, width, quantities });
+        } else {
+            anyhow::bail!("invalid region line: {}", line)
+        }
+    }
+
+    #[cfg(test)]
+    {
+        println!("Shapes:\n{:?}", shapes);
+        println!("Regions:\n{:?}", regions);
+    }
+
+    Ok((shapes, regions))
+}
+
+/// Number of regions the presents fit in, from every region's [`Region::pack`].
+fn part1(packings: &[Packing]) -> usize {
+    packings.iter().filter(|p| p.placement().is_some()).count()
+}
+
+#[derive(Parser)]
+struct Args {
+    /// Give up on a region whose search takes longer than this many seconds.
+    #[arg(long)]
+    time_budget: Option<f64>,
+    /// Draw every region the presents fit in, one letter per present.
+    #[arg(long)]
+    show: bool,
+    /// Tell for every region the presents don't fit in whether the area alone rules it out or it
+    /// took a full search.
+    #[arg(long)]
+    unsat_reason: bool,
+    /// Worker threads for packing regions, 0 for one per CPU. More than one needs the
+    /// `parallel` feature.
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    parallel::set_threads(args.threads).map_err(anyhow::Error::msg)?;
+    let budget = args.time_budget.map(Duration::from_secs_f64);
+    let contents = std::fs::read_to_string("./data/day-12-input.txt")?;
+    let (shapes, regions) = parse_input_spec(&contents)?;
+
+    let packings =
+        parallel::map(&regions, |region| region.pack(&shapes, budget));
+    for (i, (region, packing)) in regions.iter().zip(&packings).enumerate() {
+        let name =
+            format!("Region {} ({}x{})", i, region.width, region.length);
+        match packing {
+            Packing::Fits(placement) => {
+                region
+                    .check(&shapes, placement)
+                    .with_context(|| format!("{}: bad placement", name))?;
+                if args.show {
+                    println!(
+                        "{}:\n{}",
+                        name,
+                        placement::render(
+                            &shapes,
+                            region.width,
+                            region.length,
+                            placement
+                        )
+                    );
+                }
+            }
+            Packing::TooLittleArea { needed, available } => {
+                if args.unsat_reason {
+                    println!(
+                        "{}: too little area, {} cells needed but only {}",
+                        name, needed, available
+                    );
+                }
+            }
+            Packing::NoArrangement => {
+                if args.unsat_reason {
+                    println!(
+                        "{}: enough area, but a full search found no arrangement",
+                        name
+                    );
+                }
+            }
+            // always worth knowing, since part 1 may be an undercount
+            Packing::Undecided { nodes } => {
+                eprintln!(
+                    "{}: undecided, search gave up after {} nodes",
+                    name, nodes
+                );
+            }
+        }
+    }
+
+    /* Part 1 */
+    let undecided = packings
+        .iter()
+        .filter(|p| matches!(p, Packing::Undecided { .. }))
+        .count();
+    if undecided > 0 {
+        println!(
+            "Part 1: {} (at least, {} regions undecided)",
+            part1(&packings),
+            undecided
+        );
+    } else {
+        println!("Part 1: {}", part1(&packings));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_part1_example() {
+        let input = "
+0:
+###
+##.
+##.
+
+1:
+###
+##.
+.##
+
+2:
+.##
+###
+##.
+
+3:
+##.
+###
+##.
+
+4:
+###
+#..
+###
+
+5:
+###
+.#.
+###
+
+4x4: 0 0 0 0 2 0
+12x5: 1 0 1 0 2 2
+12x5: 1 0 1 0 3 2";
+        let (shapes, regions) = parse_input_spec(input).unwrap();
+        let packings: Vec<Packing> =
+            regions.iter().map(|region| region.pack(&shapes, None)).collect();
+        assert_eq!(part1(&packings), 2);
+
+        for (region, packing) in regions.iter().zip(&packings).take(2) {
+            region.check(&shapes, packing.placement().unwrap()).unwrap();
+        }
+        // 49 cells would fit in 60, but the last region takes a full search to rule out
+        assert_eq!(packings[2], Packing::NoArrangement);
+        // with no time at all the search gives up, without failing the other regions
+        let rushed: Vec<Packing> = regions
+            .iter()
+            .map(|region| region.pack(&shapes, Some(Duration::ZERO)))
+            .collect();
+        assert!(matches!(rushed[2], Packing::Undecided { .. }));
+        // the small region is settled before the budget is first checked
+        assert_eq!(rushed[0], packings[0]);
+        assert_eq!(part1(&rushed), 1);
+    }
+
+    #[test]
+    fn test_part2_example() {
+        // TODO: fill me in
+    }
+}
@@ -0,0 +1,222 @@
+//! Packing presents into a region without overlaps, leaving gaps where needed.
+//!
+//! Cheap checks settle most regions: too little area can never work, and when every present
+//! gets a slot the size of the largest shape the answer is a plain grid of slots. Anything else
+//! becomes an exact cover problem: every cell is covered once, by a present or by a hole, with
+//! as many presents of each shape as asked for and as many holes as there is spare area.
+
+use crate::shape::Shape;
+use aoc_common::dlx::{ExactCover, Outcome};
+use std::time::Duration;
+
+/// One present put down in a region: `orientation` of `shape` with its bounding box's top left
+/// corner at `(row, col)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placed {
+    pub shape: usize,
+    pub orientation: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+/// What packing a region came to, and how it got there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packing {
+    /// Every present put down
+    Fits(Vec<Placed>),
+    /// The presents cover more cells than the region has, no search needed
+    TooLittleArea { needed: usize, available: usize },
+    /// There is room by area, but a full search found no arrangement
+    NoArrangement,
+    /// The search ran out of time after visiting `nodes` search nodes
+    Undecided { nodes: u64 },
+}
+
+impl Packing {
+    pub fn placement(&self) -> Option<&[Placed]> {
+        match self {
+            Self::Fits(placement) => Some(placement),
+            _ => None,
+        }
+    }
+}
+
+/// Tries to put down `quantities[i]` presents of each shape `i` in a `width` by `length` region,
+/// giving up as undecided once the search runs for longer than `budget`.
+pub fn pack(
+    shapes: &[Shape],
+    width: usize,
+    length: usize,
+    quantities: &[usize],
+    budget: Option<Duration>,
+) -> Packing {
+    let area: usize =
+        shapes.iter().zip(quantities).map(|(s, &q)| s.area() * q).sum();
+    if area > width * length {
+        return Packing::TooLittleArea {
+            needed: area,
+            available: width * length,
+        };
+    }
+    if let Some(placement) = pack_in_slots(shapes, width, length, quantities) {
+        return Packing::Fits(placement);
+    }
+
+    // columns: one per shape, then the holes, then one per cell
+    let holes = shapes.len();
+    let cell = |r: usize, c: usize| holes + 1 + r * width + c;
+    let mut needs = quantities.to_vec();
+    needs.push(width * length - area);
+    needs.extend(std::iter::repeat_n(1, width * length));
+    let mut cover = ExactCover::new(&needs, 0);
+
+    // what each row puts down, `None` for a hole
+    let mut rows = Vec::new();
+    for r in 0..length {
+        for c in 0..width {
+            cover.add_row(&[holes, cell(r, c)]);
+            rows.push(None);
+        }
+    }
+    for (shape, &quantity) in quantities.iter().enumerate() {
+        if quantity == 0 {
+            continue;
+        }
+        for (idx, orientation) in shapes[shape].orientations.iter().enumerate()
+        {
+            if orientation.height > length || orientation.width > width {
+                continue;
+            }
+            for row in 0..=length - orientation.height {
+                for col in 0..=width - orientation.width {
+                    let mut columns = vec![shape];
+                    columns.extend(
+                        orientation
+                            .cells
+                            .iter()
+                            .map(|&(r, c)| cell(row + r, col + c)),
+                    );
+                    cover.add_row(&columns);
+                    rows.push(Some(Placed {
+                        shape,
+                        orientation: idx,
+                        row,
+                        col,
+                    }));
+                }
+            }
+        }
+    }
+
+    match cover.first_solution(budget) {
+        Outcome::Found(picked) => Packing::Fits(
+            picked.into_iter().filter_map(|row| rows[row]).collect(),
+        ),
+        Outcome::NoSolution => Packing::NoArrangement,
+        Outcome::OutOfTime(progress) => {
+            Packing::Undecided { nodes: progress.nodes }
+        }
+    }
+}
+
+/// Puts every present, as given, in its own slot of a grid of slots the size of the largest
+/// shape's bounding box, if there are enough slots.
+fn pack_in_slots(
+    shapes: &[Shape],
+    width: usize,
+    length: usize,
+    quantities: &[usize],
+) -> Option<Vec<Placed>> {
+    let used = shapes.iter().zip(quantities).filter(|(_, q)| **q > 0);
+    let slot_height =
+        used.clone().map(|(s, _)| s.orientations[0].height).max()?;
+    let slot_width = used.map(|(s, _)| s.orientations[0].width).max()?;
+    let across = width / slot_width;
+    let slots = across * (length / slot_height);
+    if quantities.iter().sum::<usize>() > slots {
+        return None;
+    }
+    let presents = quantities
+        .iter()
+        .enumerate()
+        .flat_map(|(shape, &q)| std::iter::repeat_n(shape, q));
+    Some(
+        presents
+            .enumerate()
+            .map(|(slot, shape)| Placed {
+                shape,
+                orientation: 0,
+                row: slot / across * slot_height,
+                col: slot % across * slot_width,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn shapes(diagrams: &[&str]) -> Vec<Shape> {
+        diagrams.iter().map(|diagram| diagram.parse().unwrap()).collect()
+    }
+
+    /// Cells covered by `placement`, checking along the way that nothing overlaps or sticks
+    /// out.
+    fn cover(
+        shapes: &[Shape],
+        width: usize,
+        length: usize,
+        placement: &[Placed],
+    ) -> usize {
+        let mut grid = vec![vec![false; width]; length];
+        for placed in placement {
+            let orientation =
+                &shapes[placed.shape].orientations[placed.orientation];
+            for &(r, c) in &orientation.cells {
+                let cell = &mut grid[placed.row + r][placed.col + c];
+                assert!(!*cell, "overlap at {:?}", (r, c));
+                *cell = true;
+            }
+        }
+        grid.iter().flatten().filter(|&&c| c).count()
+    }
+
+    #[test]
+    fn test_area_and_slots() {
+        let shapes = shapes(&["###\n#..\n###", "##\n##"]);
+        // 7 + 7 + 4 cells don't fit in 17
+        assert_eq!(
+            pack(&shapes, 17, 1, &[2, 1], None),
+            Packing::TooLittleArea { needed: 18, available: 17 }
+        );
+        // four 3x3 slots in a 7x6 region, used in order
+        let packing = pack(&shapes, 7, 6, &[3, 1], None);
+        let placement = packing.placement().unwrap();
+        assert_eq!(placement.len(), 4);
+        assert_eq!(
+            placement[3],
+            Placed { shape: 1, orientation: 0, row: 3, col: 3 }
+        );
+        assert_eq!(cover(&shapes, 7, 6, placement), 25);
+    }
+
+    #[test]
+    fn test_search_with_gaps() {
+        // two L trominoes make a 3x2 block, too tight for 2x2 slots
+        let shapes = shapes(&["##\n#.", "#"]);
+        let packing = pack(&shapes, 3, 2, &[2, 0], None);
+        let placement = packing.placement().unwrap();
+        assert_eq!(cover(&shapes, 3, 2, placement), 6);
+        // with gaps left over for single cells
+        let packing = pack(&shapes, 4, 2, &[2, 2], None);
+        let placement = packing.placement().unwrap();
+        assert_eq!(cover(&shapes, 4, 2, placement), 8);
+        let packing = pack(&shapes, 5, 2, &[2, 1], None);
+        let placement = packing.placement().unwrap();
+        assert_eq!(cover(&shapes, 5, 2, placement), 7);
+        // a 2x2 square has room by area but never fits a 1 cell wide region
+        let shapes = self::shapes(&["##\n##"]);
+        assert_eq!(pack(&shapes, 1, 8, &[1], None), Packing::NoArrangement);
+    }
+}
@@ -0,0 +1,132 @@
+//! Checking and drawing the presents put down in a region.
+
+use crate::packing::Placed;
+use crate::shape::Shape;
+
+/// Letter for the `i`th present put down: `A` to `Z`, then `a` to `z`, then round again.
+fn letter(i: usize) -> char {
+    const LETTERS: &[u8; 52] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+    LETTERS[i % LETTERS.len()] as char
+}
+
+/// Confirms that `placement` puts down exactly `quantities[i]` presents of each shape `i`, all
+/// inside the `width` by `length` region and none overlapping another.
+///
+/// # Errors
+///
+/// Describing the first present that breaks one of these rules.
+pub fn check(
+    shapes: &[Shape],
+    width: usize,
+    length: usize,
+    quantities: &[usize],
+    placement: &[Placed],
+) -> anyhow::Result<()> {
+    let mut grid: Vec<Vec<Option<usize>>> = vec![vec![None; width]; length];
+    let mut counts = vec![0; shapes.len()];
+    for (i, placed) in placement.iter().enumerate() {
+        let orientation = shapes
+            .get(placed.shape)
+            .and_then(|shape| shape.orientations.get(placed.orientation))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "present {} has no shape {} orientation {}",
+                    letter(i),
+                    placed.shape,
+                    placed.orientation
+                )
+            })?;
+        counts[placed.shape] += 1;
+        for &(r, c) in &orientation.cells {
+            let (row, col) = (placed.row + r, placed.col + c);
+            if row >= length || col >= width {
+                anyhow::bail!(
+                    "present {} sticks out of the {}x{} region at {:?}",
+                    letter(i),
+                    width,
+                    length,
+                    (row, col)
+                );
+            }
+            if let Some(other) = grid[row][col] {
+                anyhow::bail!(
+                    "presents {} and {} overlap at {:?}",
+                    letter(other),
+                    letter(i),
+                    (row, col)
+                );
+            }
+            grid[row][col] = Some(i);
+        }
+    }
+    if counts != quantities {
+        anyhow::bail!(
+            "put down {:?} presents of each shape, expected {:?}",
+            counts,
+            quantities
+        );
+    }
+    Ok(())
+}
+
+/// Draws the region the way shapes are drawn in the input, with each present's cells marked by
+/// its own letter instead of `#`, and free cells as `.`.
+///
+/// Presents must be in bounds, see [`check`]; overlapping cells show the later present.
+pub fn render(
+    shapes: &[Shape],
+    width: usize,
+    length: usize,
+    placement: &[Placed],
+) -> String {
+    let mut grid = vec![vec!['.'; width]; length];
+    for (i, placed) in placement.iter().enumerate() {
+        let orientation =
+            &shapes[placed.shape].orientations[placed.orientation];
+        for &(r, c) in &orientation.cells {
+            grid[placed.row + r][placed.col + c] = letter(i);
+        }
+    }
+    grid.iter().map(|row| row.iter().collect::<String>() + "\n").collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_and_render() {
+        let shapes: Vec<Shape> =
+            ["##\n#.", "#"].iter().map(|d| d.parse().unwrap()).collect();
+        let l =
+            |orientation, row, col| Placed { shape: 0, orientation, row, col };
+        let dot = Placed { shape: 1, orientation: 0, row: 0, col: 3 };
+        // the L as drawn, and turned half way round to fill its corner
+        let placement = [l(0, 0, 0), l(2, 0, 1), dot];
+        check(&shapes, 4, 2, &[2, 1], &placement).unwrap();
+        assert_eq!(render(&shapes, 4, 2, &placement), "AABC\nABB.\n");
+
+        let err = |placement: &[Placed], quantities: &[usize]| {
+            check(&shapes, 4, 2, quantities, placement)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            err(&[l(0, 0, 0), l(0, 0, 1)], &[2, 0]),
+            "presents A and B overlap at (0, 1)"
+        );
+        assert_eq!(
+            err(&[l(0, 1, 0)], &[1, 0]),
+            "present A sticks out of the 4x2 region at (2, 0)"
+        );
+        assert_eq!(
+            err(&[dot], &[0, 2]),
+            "put down [0, 1] presents of each shape, expected [0, 2]"
+        );
+        assert_eq!(
+            err(&[l(8, 0, 0)], &[1, 0]),
+            "present A has no shape 0 orientation 8"
+        );
+    }
+}
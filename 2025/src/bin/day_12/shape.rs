@@ -0,0 +1,137 @@
+//! Present shapes and the distinct ways they can be turned over and rotated.
+
+use std::str::FromStr;
+
+/// A present, as every way it can be put down.
+#[derive(Debug)]
+pub struct Shape {
+    /// Every distinct rotation and reflection, the diagram as given first
+    pub orientations: Vec<Orientation>,
+}
+
+/// A shape turned one particular way, moved up against the top left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orientation {
+    /// Occupied `(row, col)` cells, ascending
+    pub cells: Vec<(usize, usize)>,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Orientation {
+    fn new(cells: &[(isize, isize)]) -> Self {
+        let min_row = cells.iter().map(|c| c.0).min().unwrap_or(0);
+        let min_col = cells.iter().map(|c| c.1).min().unwrap_or(0);
+        let mut cells: Vec<(usize, usize)> = cells
+            .iter()
+            .map(|&(r, c)| ((r - min_row) as usize, (c - min_col) as usize))
+            .collect();
+        cells.sort_unstable();
+        let height = cells.iter().map(|c| c.0 + 1).max().unwrap_or(0);
+        let width = cells.iter().map(|c| c.1 + 1).max().unwrap_or(0);
+        Self { cells, height, width }
+    }
+}
+
+impl Shape {
+    /// Shape from its diagram, `true` for the occupied cells.
+    ///
+    /// # Errors
+    ///
+    /// If the diagram has no occupied cells.
+    pub fn new(display: &[Vec<bool>]) -> anyhow::Result<Self> {
+        let cells: Vec<(isize, isize)> = display
+            .iter()
+            .enumerate()
+            .flat_map(|(r, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, on)| **on)
+                    .map(move |(c, _)| (r as isize, c as isize))
+            })
+            .collect();
+        if cells.is_empty() {
+            anyhow::bail!("shape has no cells");
+        }
+
+        let mut orientations: Vec<Orientation> = Vec::new();
+        let mut turned = cells;
+        for _ in 0..2 {
+            for _ in 0..4 {
+                let orientation = Orientation::new(&turned);
+                if !orientations.contains(&orientation) {
+                    orientations.push(orientation);
+                }
+                // quarter turn clockwise
+                turned = turned.iter().map(|&(r, c)| (c, -r)).collect();
+            }
+            // mirror left to right
+            turned = turned.iter().map(|&(r, c)| (r, -c)).collect();
+        }
+        Ok(Self { orientations })
+    }
+
+    /// Number of occupied cells.
+    pub fn area(&self) -> usize {
+        self.orientations[0].cells.len()
+    }
+}
+
+impl FromStr for Shape {
+    type Err = anyhow::Error;
+
+    /// Shape from its diagram as drawn in the input, `#` for the occupied cells and `.` for the
+    /// free ones.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let display = s
+            .lines()
+            .map(|row| {
+                row.chars()
+                    .map(|c| match c {
+                        '#' => Ok(true),
+                        '.' => Ok(false),
+                        _ => anyhow::bail!(
+                            "Invalid character in shape display: {}",
+                            c
+                        ),
+                    })
+                    .collect::<anyhow::Result<Vec<bool>>>()
+            })
+            .collect::<anyhow::Result<Vec<Vec<bool>>>>()?;
+        Self::new(&display)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn shape(diagram: &str) -> Shape {
+        diagram.parse().unwrap()
+    }
+
+    #[test]
+    fn test_distinct_orientations() {
+        assert_eq!(shape("###\n###\n###").orientations.len(), 1);
+        assert_eq!(shape("###\n.#.\n###").orientations.len(), 2);
+        assert_eq!(shape("###\n#..\n###").orientations.len(), 4);
+        // no symmetry at all
+        assert_eq!(shape("###\n##.\n.##").orientations.len(), 8);
+
+        let l = shape("#..\n###");
+        assert_eq!(l.area(), 4);
+        assert_eq!(l.orientations.len(), 8);
+        let first = &l.orientations[0];
+        assert_eq!((first.height, first.width), (2, 3));
+        assert_eq!(first.cells, vec![(0, 0), (1, 0), (1, 1), (1, 2)]);
+        // a quarter turn clockwise stands it up
+        let turned = &l.orientations[1];
+        assert_eq!((turned.height, turned.width), (3, 2));
+        assert_eq!(turned.cells, vec![(0, 0), (0, 1), (1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn test_empty_shape_rejected() {
+        assert!(Shape::new(&vec![vec![false; 3]; 3]).is_err());
+    }
+}
@@ -0,0 +1,473 @@
+//! Exact cover by Knuth's Algorithm X on dancing links, with multiplicities.
+//!
+//! Every primary column must be covered exactly as many times as it asks for, and every
+//! secondary column at most once. Each solution is found once as a set of rows, whatever the
+//! order rows could be picked in.
+//!
+//! See: <https://en.wikipedia.org/wiki/Dancing_Links>
+
+use std::{
+    ops::ControlFlow,
+    time::{Duration, Instant},
+};
+
+/// Number of search nodes between two [`Event::Progress`] reports.
+pub const PROGRESS_INTERVAL: u64 = 4096;
+
+/// What the search reports to its observer.
+#[derive(Debug)]
+pub enum Event<'a> {
+    /// A solution, as the ids of its rows in the order they were picked
+    Solution(&'a [usize]),
+    /// Sent every [`PROGRESS_INTERVAL`] search nodes
+    Progress(Progress),
+}
+
+/// How far a search has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Rows tried so far
+    pub nodes: u64,
+    /// Solutions found so far
+    pub solutions: u64,
+    /// Rows picked on the current branch
+    pub depth: usize,
+}
+
+/// Result of looking for a single solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Row ids of a solution, ascending
+    Found(Vec<usize>),
+    /// The whole search space was tried
+    NoSolution,
+    /// The time budget ran out first
+    OutOfTime(Progress),
+}
+
+/// An exact cover problem: columns to cover, and rows that each cover some of them.
+#[derive(Debug, Clone)]
+pub struct ExactCover {
+    // circular doubly linked lists in both directions, as node indices: node 0 is the root,
+    // nodes 1..=columns the column headers, the rest row cells
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// Column header of every node
+    header: Vec<usize>,
+    /// Row id of every row cell
+    row: Vec<usize>,
+    /// Rows still linked into each column, by header
+    len: Vec<usize>,
+    /// Covers still needed for each primary column, by header
+    need: Vec<usize>,
+    /// First cell of every row
+    row_start: Vec<usize>,
+    primary: usize,
+    columns: usize,
+}
+
+impl ExactCover {
+    /// Problem with a primary column `i` for every `primary[i]`, that must be covered exactly
+    /// that many times, followed by `secondary` columns that may be covered at most once.
+    pub fn new(primary: &[usize], secondary: usize) -> Self {
+        let columns = primary.len() + secondary;
+        let nodes = columns + 1;
+        let mut cover = Self {
+            left: (0..nodes).collect(),
+            right: (0..nodes).collect(),
+            up: (0..nodes).collect(),
+            down: (0..nodes).collect(),
+            header: (0..nodes).collect(),
+            row: vec![usize::MAX; nodes],
+            len: vec![0; nodes],
+            need: vec![0; nodes],
+            row_start: Vec::new(),
+            primary: primary.len(),
+            columns,
+        };
+        // only primary columns that need covering go in the root's list; a column that needs
+        // nothing counts as covered from the start
+        let mut last = 0;
+        for (i, &need) in primary.iter().enumerate() {
+            let h = i + 1;
+            cover.need[h] = need;
+            if need > 0 {
+                cover.right[last] = h;
+                cover.left[h] = last;
+                last = h;
+            }
+        }
+        cover.right[last] = 0;
+        cover.left[0] = last;
+        cover
+    }
+
+    /// Adds a row covering `columns` once each, returning its id. Row ids count up from 0.
+    ///
+    /// A row using a primary column that needs no covers can never be picked.
+    ///
+    /// # Panics
+    ///
+    /// If a column is out of range or repeated, or the row is empty.
+    pub fn add_row(&mut self, columns: &[usize]) -> usize {
+        assert!(!columns.is_empty(), "empty row");
+        let id = self.row_start.len();
+        let first = self.header.len();
+        let live =
+            columns.iter().all(|&c| c >= self.primary || self.need[c + 1] > 0);
+        self.row_start.push(first);
+        for (k, &column) in columns.iter().enumerate() {
+            assert!(column < self.columns, "column {} out of range", column);
+            assert!(
+                !columns[..k].contains(&column),
+                "column {} repeated",
+                column
+            );
+            let h = column + 1;
+            let node = self.header.len();
+            self.header.push(h);
+            self.row.push(id);
+            if live {
+                // append to the bottom of the column
+                self.up.push(self.up[h]);
+                self.down.push(h);
+                self.down[self.up[h]] = node;
+                self.up[h] = node;
+                self.len[h] += 1;
+            } else {
+                self.up.push(node);
+                self.down.push(node);
+            }
+            // and to the end of the row
+            let (prev, next) =
+                if k == 0 { (node, node) } else { (node - 1, first) };
+            self.left.push(prev);
+            self.right.push(next);
+            self.right[prev] = node;
+            self.left[next] = node;
+        }
+        id
+    }
+
+    /// Runs the search, passing every solution and regular progress reports to `observe` until
+    /// it breaks or the search space is exhausted. Returns the final progress.
+    pub fn search(
+        &mut self,
+        mut observe: impl FnMut(Event) -> ControlFlow<()>,
+    ) -> Progress {
+        let mut progress = Progress { nodes: 0, solutions: 0, depth: 0 };
+        let mut picked = Vec::new();
+        let _ = self.solve(&mut picked, &mut progress, &mut observe);
+        progress.depth = 0;
+        progress
+    }
+
+    /// Number of distinct solutions.
+    pub fn count_solutions(&mut self) -> u64 {
+        self.search(|_| ControlFlow::Continue(())).solutions
+    }
+
+    /// The first solution found, giving up once `budget` has passed if there is one.
+    pub fn first_solution(&mut self, budget: Option<Duration>) -> Outcome {
+        let start = Instant::now();
+        let mut found = None;
+        let mut timed_out = None;
+        self.search(|event| match event {
+            Event::Solution(rows) => {
+                let mut rows = rows.to_vec();
+                rows.sort_unstable();
+                found = Some(rows);
+                ControlFlow::Break(())
+            }
+            Event::Progress(progress) => {
+                if budget.is_some_and(|budget| start.elapsed() > budget) {
+                    timed_out = Some(progress);
+                    return ControlFlow::Break(());
+                }
+                ControlFlow::Continue(())
+            }
+        });
+        match (found, timed_out) {
+            (Some(rows), _) => Outcome::Found(rows),
+            (None, Some(progress)) => Outcome::OutOfTime(progress),
+            (None, None) => Outcome::NoSolution,
+        }
+    }
+
+    fn solve(
+        &mut self,
+        picked: &mut Vec<usize>,
+        progress: &mut Progress,
+        observe: &mut impl FnMut(Event) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        if self.right[0] == 0 {
+            progress.solutions += 1;
+            return observe(Event::Solution(picked));
+        }
+
+        // the column with the fewest spare rows; none at all means a dead end
+        let mut best = None;
+        let mut h = self.right[0];
+        while h != 0 {
+            let Some(spare) = self.len[h].checked_sub(self.need[h]) else {
+                return ControlFlow::Continue(());
+            };
+            if best.is_none_or(|(_, fewest)| spare < fewest) {
+                best = Some((h, spare));
+            }
+            h = self.right[h];
+        }
+        let (column, _) = best.expect("at least one column left");
+
+        // each row of the column in turn; once all solutions with a row are found it is
+        // hidden, so no set of rows comes up twice
+        let mut excluded = Vec::new();
+        let mut node = self.down[column];
+        let mut flow = ControlFlow::Continue(());
+        while node != column {
+            progress.nodes += 1;
+            if progress.nodes.is_multiple_of(PROGRESS_INTERVAL) {
+                progress.depth = picked.len();
+                flow = observe(Event::Progress(*progress));
+                if flow.is_break() {
+                    break;
+                }
+            }
+
+            let row = self.row[node];
+            let covered = self.pick(row);
+            picked.push(row);
+            flow = self.solve(picked, progress, observe);
+            picked.pop();
+            self.unpick(row, &covered);
+            if flow.is_break() {
+                break;
+            }
+
+            self.hide_row(row);
+            excluded.push(row);
+            node = self.down[node];
+        }
+        for &row in excluded.iter().rev() {
+            self.unhide_row(row);
+        }
+        flow
+    }
+
+    /// Takes `row` into the solution, returning the columns it finished covering.
+    fn pick(&mut self, row: usize) -> Vec<usize> {
+        self.hide_row(row);
+        let mut covered = Vec::new();
+        for node in self.row_nodes(row) {
+            let h = self.header[node];
+            if self.need[h] > 1 {
+                self.need[h] -= 1;
+            } else {
+                self.need[h] = 0;
+                self.cover(h);
+                covered.push(h);
+            }
+        }
+        covered
+    }
+
+    fn unpick(&mut self, row: usize, covered: &[usize]) {
+        for &h in covered.iter().rev() {
+            self.uncover(h);
+        }
+        for node in self.row_nodes(row) {
+            let h = self.header[node];
+            if !covered.contains(&h) {
+                self.need[h] += 1;
+            } else if h <= self.primary {
+                self.need[h] = 1;
+            }
+        }
+        self.unhide_row(row);
+    }
+
+    /// Cells of `row`, left to right.
+    fn row_nodes(&self, row: usize) -> std::ops::Range<usize> {
+        let first = self.row_start[row];
+        let end =
+            self.row_start.get(row + 1).copied().unwrap_or(self.header.len());
+        first..end
+    }
+
+    /// Unlinks every cell of `row` from its column.
+    fn hide_row(&mut self, row: usize) {
+        for node in self.row_nodes(row) {
+            self.unlink_vertical(node);
+        }
+    }
+
+    fn unhide_row(&mut self, row: usize) {
+        for node in self.row_nodes(row).rev() {
+            self.relink_vertical(node);
+        }
+    }
+
+    /// Removes column `h` and every row still in it.
+    fn cover(&mut self, h: usize) {
+        self.right[self.left[h]] = self.right[h];
+        self.left[self.right[h]] = self.left[h];
+        let mut i = self.down[h];
+        while i != h {
+            let mut j = self.right[i];
+            while j != i {
+                self.unlink_vertical(j);
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, h: usize) {
+        let mut i = self.up[h];
+        while i != h {
+            let mut j = self.left[i];
+            while j != i {
+                self.relink_vertical(j);
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        self.right[self.left[h]] = h;
+        self.left[self.right[h]] = h;
+    }
+
+    fn unlink_vertical(&mut self, node: usize) {
+        self.down[self.up[node]] = self.down[node];
+        self.up[self.down[node]] = self.up[node];
+        self.len[self.header[node]] -= 1;
+    }
+
+    fn relink_vertical(&mut self, node: usize) {
+        self.down[self.up[node]] = node;
+        self.up[self.down[node]] = node;
+        self.len[self.header[node]] += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// N queens: a primary column per row and per file, secondary ones for the diagonals.
+    fn queens(n: usize) -> ExactCover {
+        let mut cover = ExactCover::new(&vec![1; 2 * n], 2 * (2 * n - 1));
+        for r in 0..n {
+            for c in 0..n {
+                let diagonal = 2 * n + r + c;
+                let anti = 2 * n + (2 * n - 1) + (r + n - 1 - c);
+                cover.add_row(&[r, n + c, diagonal, anti]);
+            }
+        }
+        cover
+    }
+
+    /// `pigeons` pigeons in `holes` holes, at most one per hole.
+    fn pigeonholes(pigeons: usize, holes: usize) -> ExactCover {
+        let mut cover = ExactCover::new(&vec![1; pigeons], holes);
+        for p in 0..pigeons {
+            for h in 0..holes {
+                cover.add_row(&[p, pigeons + h]);
+            }
+        }
+        cover
+    }
+
+    #[test]
+    fn test_knuth_example() {
+        let mut cover = ExactCover::new(&[1; 7], 0);
+        for row in [
+            &[2, 4, 5][..],
+            &[0, 3, 6],
+            &[1, 2, 5],
+            &[0, 3],
+            &[1, 6],
+            &[3, 4, 6],
+        ] {
+            cover.add_row(row);
+        }
+        assert_eq!(cover.first_solution(None), Outcome::Found(vec![0, 3, 4]));
+        assert_eq!(cover.count_solutions(), 1);
+    }
+
+    #[test]
+    fn test_secondary_columns() {
+        assert_eq!(queens(6).count_solutions(), 4);
+        assert_eq!(queens(8).count_solutions(), 92);
+        assert_eq!(pigeonholes(3, 3).count_solutions(), 6);
+        assert_eq!(pigeonholes(4, 3).count_solutions(), 0);
+    }
+
+    #[test]
+    fn test_multiplicity() {
+        // pick 2 of 4 interchangeable rows: every pair once, never twice in another order
+        let mut cover = ExactCover::new(&[2], 0);
+        (0..4).for_each(|_| _ = cover.add_row(&[0]));
+        assert_eq!(cover.count_solutions(), 6);
+
+        // 2 of column 0 and 1 of column 1, rows 0 and 1 sharing a secondary column
+        let mut cover = ExactCover::new(&[2, 1], 1);
+        cover.add_row(&[0, 2]);
+        cover.add_row(&[0, 2]);
+        cover.add_row(&[0, 1]);
+        cover.add_row(&[1]);
+        cover.add_row(&[0]);
+        let mut solutions = Vec::new();
+        cover.search(|event| {
+            if let Event::Solution(rows) = event {
+                let mut rows = rows.to_vec();
+                rows.sort_unstable();
+                solutions.push(rows);
+            }
+            ControlFlow::Continue(())
+        });
+        solutions.sort();
+        assert_eq!(
+            solutions,
+            vec![
+                vec![0, 2],
+                vec![0, 3, 4],
+                vec![1, 2],
+                vec![1, 3, 4],
+                vec![2, 4]
+            ]
+        );
+
+        // a column that needs nothing rules out its rows
+        let mut cover = ExactCover::new(&[1, 0], 0);
+        cover.add_row(&[0, 1]);
+        assert_eq!(cover.first_solution(None), Outcome::NoSolution);
+        cover.add_row(&[0]);
+        assert_eq!(cover.first_solution(None), Outcome::Found(vec![1]));
+    }
+
+    #[test]
+    fn test_progress_and_budget() {
+        let mut reports = Vec::new();
+        let end = pigeonholes(8, 7).search(|event| {
+            if let Event::Progress(progress) = event {
+                reports.push(progress);
+            }
+            ControlFlow::Continue(())
+        });
+        assert_eq!(end.solutions, 0);
+        assert_eq!(reports.len() as u64, end.nodes / PROGRESS_INTERVAL);
+        assert!(reports.windows(2).all(|w| w[0].nodes < w[1].nodes));
+        assert!(reports.iter().all(|p| p.depth < 8));
+
+        let outcome = pigeonholes(8, 7).first_solution(Some(Duration::ZERO));
+        assert!(
+            matches!(outcome, Outcome::OutOfTime(p) if p.nodes == PROGRESS_INTERVAL)
+        );
+        assert_eq!(
+            pigeonholes(8, 7).first_solution(None),
+            Outcome::NoSolution
+        );
+    }
+}
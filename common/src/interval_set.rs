@@ -0,0 +1,347 @@
+//! Sets of integers stored as disjoint inclusive intervals.
+
+use std::{
+    cmp::{max, min},
+    collections::BTreeMap,
+    fmt::Debug,
+    ops::RangeInclusive,
+};
+
+/// Integer types that can be stored in an [`IntervalSet`].
+pub trait Step: Copy + Ord + Debug {
+    /// The next value up, or `None` at the type's maximum.
+    fn successor(self) -> Option<Self>;
+    /// The next value down, or `None` at the type's minimum.
+    fn predecessor(self) -> Option<Self>;
+    /// Number of values in `start..=end`, assuming `start <= end`.
+    fn count(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_step {
+    ($($t:ty),*) => {
+        $(
+            impl Step for $t {
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn predecessor(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn count(start: Self, end: Self) -> u128 {
+                    (end as i128 - start as i128) as u128 + 1
+                }
+            }
+        )*
+    };
+}
+
+impl_step!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// A set of integers stored as sorted, disjoint, non-adjacent inclusive intervals.
+///
+/// Overlapping or touching intervals are coalesced on insertion, so the intervals yielded by
+/// [`IntervalSet::iter`] are always the minimal description of the set.
+///
+/// See: <https://en.wikipedia.org/wiki/Interval_tree>
+#[derive(Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    /// start -> end of every interval
+    intervals: BTreeMap<T, T>,
+    /// Number of values in the set, kept up to date on every change
+    len: u128,
+}
+
+impl<T: Step> IntervalSet<T> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self { intervals: BTreeMap::new(), len: 0 }
+    }
+
+    /// Number of values in the set, O(1).
+    pub fn len(&self) -> u128 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of disjoint intervals making up the set.
+    pub fn interval_count(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Whether `value` is in the set, O(log n).
+    pub fn contains(&self, value: T) -> bool {
+        self.interval_containing(value).is_some()
+    }
+
+    /// The interval of the set that contains `value`, if any, O(log n).
+    pub fn interval_containing(&self, value: T) -> Option<RangeInclusive<T>> {
+        self.intervals
+            .range(..=value)
+            .next_back()
+            .filter(|&(_, &end)| value <= end)
+            .map(|(&start, &end)| start..=end)
+    }
+
+    /// The closest intervals entirely below and entirely above `value`, O(log n).
+    pub fn neighbours(
+        &self,
+        value: T,
+    ) -> (Option<RangeInclusive<T>>, Option<RangeInclusive<T>>) {
+        let below = self
+            .intervals
+            .range(..=value)
+            .map(|(&start, &end)| start..=end)
+            .rfind(|interval| *interval.end() < value);
+        let above = self
+            .intervals
+            .range(value..)
+            .map(|(&start, &end)| start..=end)
+            .find(|interval| *interval.start() > value);
+        (below, above)
+    }
+
+    /// Adds every value in `range` to the set. Empty ranges are ignored.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        // absorb an interval starting at or before `start` that overlaps or touches it
+        if let Some((&prev_start, &prev_end)) =
+            self.intervals.range(..=start).next_back()
+            && (prev_end >= start || prev_end.successor() == Some(start))
+        {
+            if prev_end >= end {
+                return; // already covered
+            }
+            self.remove_entry(prev_start);
+            start = prev_start;
+        }
+
+        // absorb every interval starting inside `start..=end + 1`
+        loop {
+            let next = match end.successor() {
+                Some(after) => self.intervals.range(start..=after).next(),
+                None => self.intervals.range(start..).next(),
+            };
+            let Some((&next_start, &next_end)) = next else {
+                break;
+            };
+            self.remove_entry(next_start);
+            end = max(end, next_end);
+        }
+
+        self.insert_entry(start, end);
+    }
+
+    /// Removes every value in `range` from the set, splitting intervals as needed.
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let mut overlapping: Vec<(T, T)> = self
+            .intervals
+            .range(..start)
+            .next_back()
+            .filter(|&(_, &prev_end)| prev_end >= start)
+            .map(|(&s, &e)| (s, e))
+            .into_iter()
+            .collect();
+        overlapping
+            .extend(self.intervals.range(start..=end).map(|(&s, &e)| (s, e)));
+
+        for (s, e) in overlapping {
+            self.remove_entry(s);
+            if s < start {
+                let before = start.predecessor().expect("s < start");
+                self.insert_entry(s, before);
+            }
+            if e > end {
+                let after = end.successor().expect("e > end");
+                self.insert_entry(after, e);
+            }
+        }
+    }
+
+    /// Intervals of the set in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.intervals.iter().map(|(&start, &end)| start..=end)
+    }
+
+    /// The values missing between the first and last interval, as ascending intervals.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.intervals.iter().zip(self.intervals.iter().skip(1)).map(
+            |((_, &end), (&next_start, _))| {
+                // intervals never touch, so both neighbours of a gap exist
+                let gap_start = end.successor().expect("gap after interval");
+                let gap_end =
+                    next_start.predecessor().expect("gap before interval");
+                gap_start..=gap_end
+            },
+        )
+    }
+
+    /// Values in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let (mut larger, smaller) =
+            if self.interval_count() >= other.interval_count() {
+                (self.clone(), other)
+            } else {
+                (other.clone(), self)
+            };
+        larger.extend(smaller.iter());
+        larger
+    }
+
+    /// Values in both sets, in O(n + m).
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Self::new();
+        let mut lhs = self.iter().peekable();
+        let mut rhs = other.iter().peekable();
+        while let (Some(a), Some(b)) = (lhs.peek(), rhs.peek()) {
+            let start = max(*a.start(), *b.start());
+            let end = min(*a.end(), *b.end());
+            if start <= end {
+                // inputs are disjoint and sorted, so pieces arrive in order and never touch
+                result.insert_entry(start, end);
+            }
+            if a.end() < b.end() {
+                lhs.next();
+            } else {
+                rhs.next();
+            }
+        }
+        result
+    }
+
+    /// Values in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.remove(range);
+        }
+        result
+    }
+
+    fn insert_entry(&mut self, start: T, end: T) {
+        self.len += T::count(start, end);
+        self.intervals.insert(start, end);
+    }
+
+    fn remove_entry(&mut self, start: T) {
+        let end = self.intervals.remove(&start).expect("interval start");
+        self.len -= T::count(start, end);
+    }
+}
+
+impl<T: Step> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Step> Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Step> Extend<RangeInclusive<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+impl<T: Step> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn set(ranges: &[(i32, i32)]) -> IntervalSet<i32> {
+        ranges.iter().map(|&(s, e)| s..=e).collect()
+    }
+
+    fn intervals(set: &IntervalSet<i32>) -> Vec<(i32, i32)> {
+        set.iter().map(|r| (*r.start(), *r.end())).collect()
+    }
+
+    #[test]
+    fn test_insert_merges_overlapping_and_adjacent() {
+        let s = set(&[(3, 5), (10, 14), (16, 20), (12, 18), (6, 6), (30, 29)]);
+        assert_eq!(intervals(&s), vec![(3, 6), (10, 20)]);
+        assert_eq!(s.len(), 15);
+
+        let mut s = s;
+        s.insert(0..=40);
+        assert_eq!(intervals(&s), vec![(0, 40)]);
+        assert_eq!(s.len(), 41);
+    }
+
+    #[test]
+    fn test_remove_splits() {
+        let mut s = set(&[(0, 10), (20, 30)]);
+        s.remove(5..=22);
+        assert_eq!(intervals(&s), vec![(0, 4), (23, 30)]);
+        assert_eq!(s.len(), 13);
+        s.remove(0..=0);
+        s.remove(30..=100);
+        assert_eq!(intervals(&s), vec![(1, 4), (23, 29)]);
+        assert_eq!(s.len(), 11);
+    }
+
+    #[test]
+    fn test_point_queries() {
+        let s = set(&[(3, 5), (10, 20)]);
+        assert!(s.contains(3) && s.contains(5) && s.contains(15));
+        assert!(!s.contains(2) && !s.contains(6) && !s.contains(21));
+        assert_eq!(s.interval_containing(12), Some(10..=20));
+        assert_eq!(s.neighbours(8), (Some(3..=5), Some(10..=20)));
+        assert_eq!(s.neighbours(25), (Some(10..=20), None));
+        assert_eq!(s.neighbours(12), (Some(3..=5), None));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (40, 50)]);
+        assert_eq!(intervals(&a.union(&b)), vec![(0, 30), (40, 50)]);
+        assert_eq!(intervals(&a.intersection(&b)), vec![(5, 10), (20, 25)]);
+        assert_eq!(intervals(&a.difference(&b)), vec![(0, 4), (26, 30)]);
+        assert_eq!(intervals(&b.difference(&a)), vec![(11, 19), (40, 50)]);
+        assert_eq!(a.union(&b).len(), 42);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let s = set(&[(0, 2), (5, 5), (7, 9)]);
+        let gaps: Vec<_> = s.gaps().collect();
+        assert_eq!(gaps, vec![3..=4, 6..=6]);
+    }
+
+    #[test]
+    fn test_type_bounds() {
+        let mut s: IntervalSet<u8> = [0..=10, 250..=255].into_iter().collect();
+        s.insert(11..=249);
+        assert_eq!(s.len(), 256);
+        s.remove(0..=0);
+        s.remove(255..=255);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![1..=254]);
+    }
+}
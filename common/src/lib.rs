@@ -0,0 +1,8 @@
+//! Data structures shared between puzzle solutions (and anything else that wants them).
+
+pub mod dlx;
+pub mod interval_set;
+pub mod parallel;
+pub mod sections;
+
+pub use interval_set::IntervalSet;
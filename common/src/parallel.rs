@@ -0,0 +1,62 @@
+//! Independent per-record work, spread over a thread pool when built with the `parallel`
+//! feature and run on the calling thread otherwise.
+//!
+//! Results always come back in input order, so answers don't depend on the number of threads.
+
+/// Runs [`map`] on `threads` worker threads from now on, or one per CPU for 0.
+///
+/// Without the `parallel` feature only a single thread is available.
+///
+/// # Errors
+///
+/// If the thread pool was already set up, or more than one thread is asked for without the
+/// `parallel` feature.
+pub fn set_threads(threads: usize) -> Result<(), String> {
+    #[cfg(feature = "parallel")]
+    {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .map_err(|e| e.to_string())
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        if threads > 1 {
+            return Err(format!(
+                "{} threads asked for, but built without the `parallel` feature",
+                threads
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Applies `f` to every item, returning the results in the order of `items`.
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_map_keeps_order() {
+        let items: Vec<u64> = (0..10_000).collect();
+        let squares = map(&items, |&x| x * x);
+        assert_eq!(squares, items.iter().map(|x| x * x).collect::<Vec<_>>());
+    }
+}
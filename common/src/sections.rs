@@ -0,0 +1,121 @@
+//! Parsing of inputs made of blank-line separated blocks of lines.
+
+use std::fmt::{self, Display};
+
+/// A single non-blank input line, remembering where it came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    /// 1-based line number in the original input
+    pub number: usize,
+    /// Line contents without the line ending or trailing whitespace
+    pub text: &'a str,
+}
+
+/// A run of consecutive non-blank lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    pub lines: Vec<Line<'a>>,
+}
+
+/// Error pointing at the input line that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineError {
+    /// 1-based line number in the original input
+    pub line: usize,
+    /// The offending line
+    pub text: String,
+    pub message: String,
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {} (found '{}')",
+            self.line, self.message, self.text
+        )
+    }
+}
+
+impl std::error::Error for LineError {}
+
+impl<'a> Section<'a> {
+    /// Line number of the first line of the section.
+    pub fn first_line(&self) -> usize {
+        self.lines[0].number
+    }
+
+    /// Parses every line with `parse`, stopping at the first failure.
+    pub fn parse_lines<T, E: Display>(
+        &self,
+        mut parse: impl FnMut(&'a str) -> Result<T, E>,
+    ) -> Result<Vec<T>, LineError> {
+        self.lines
+            .iter()
+            .map(|line| {
+                parse(line.text).map_err(|e| LineError {
+                    line: line.number,
+                    text: line.text.to_string(),
+                    message: e.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Splits `input` into sections separated by one or more blank lines.
+///
+/// CRLF line endings and trailing whitespace are stripped, and lines holding only whitespace
+/// count as blank, so leading and trailing blank lines never produce empty sections.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut current = Vec::new();
+    for (idx, text) in input.lines().enumerate() {
+        let text = text.trim_end();
+        if text.is_empty() {
+            if !current.is_empty() {
+                sections.push(Section { lines: std::mem::take(&mut current) });
+            }
+        } else {
+            current.push(Line { number: idx + 1, text });
+        }
+    }
+    if !current.is_empty() {
+        sections.push(Section { lines: current });
+    }
+    sections
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sections_split_on_blank_lines() {
+        let input = "\r\n1-2 \r\n3-4\r\n\r\n   \r\n5\r\n6\t\r\n\r\n";
+        let sections = sections(input);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(
+            sections[0].lines,
+            vec![
+                Line { number: 2, text: "1-2" },
+                Line { number: 3, text: "3-4" }
+            ]
+        );
+        assert_eq!(sections[1].first_line(), 6);
+        assert_eq!(sections[1].lines[1].text, "6");
+    }
+
+    #[test]
+    fn test_parse_lines_reports_line_number() {
+        let input = "1\n2\nthree\n4";
+        let sections = sections(input);
+        let err = sections[0].parse_lines(str::parse::<usize>).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.text, "three");
+        assert_eq!(
+            err.to_string(),
+            "line 3: invalid digit found in string (found 'three')"
+        );
+    }
+}